@@ -1,7 +1,6 @@
 use clap::ValueEnum;
 use std::process::{Command, Stdio};
 use std::path::{Path, PathBuf};
-use tempfile;
 use std::io::{self, Read, Write};
 use std::fs::File;
 use std::cmp::{min, max};
@@ -12,6 +11,197 @@ pub enum Shape {
     Ellipse,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum PitchMode {
+    /// Change the tempo of the audio while keeping its pitch
+    Preserve,
+    /// Resample the audio so its pitch follows the speed change
+    Shift,
+    /// Remove the audio track
+    DropAudio,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum Interpolation {
+    /// Motion-compensated interpolation (minterpolate), smoothest but slow
+    Motion,
+    /// Blend neighbouring frames (framerate), fast
+    Blend,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum BoomerangAudio {
+    /// Drop the audio track
+    Mute,
+    /// Play the audio forward, then backward along with the video
+    Reverse,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum Anchor {
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CropTarget {
+    /// Largest area with this aspect ratio (width, height)
+    Aspect(u32, u32),
+    /// Exact size in pixels (width, height), scaled down to fit if needed
+    Size(u32, u32),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum FitBackground {
+    /// Blurred, scaled-up copy of the video
+    Blur,
+    /// Solid color
+    Color,
+    /// Still image
+    Image,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum SlideOrder {
+    /// File name, byte by byte
+    Name,
+    /// File name with numbers compared by value (img2 before img10)
+    Natural,
+    /// File modification time
+    Mtime,
+    /// EXIF capture date, falling back to the modification time
+    ExifDate,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum SlideFit {
+    /// Show the whole image, padding with black bars
+    Contain,
+    /// Fill the canvas, cropping the edges of the image
+    Cover,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum KenBurns {
+    /// Pick a direction for each image
+    Random,
+    ZoomIn,
+    ZoomOut,
+    PanLeft,
+    PanRight,
+    PanUp,
+    PanDown,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum Transition {
+    Fade,
+    FadeBlack,
+    FadeWhite,
+    Dissolve,
+    WipeLeft,
+    WipeRight,
+    WipeUp,
+    WipeDown,
+    SlideLeft,
+    SlideRight,
+    SlideUp,
+    SlideDown,
+    CircleOpen,
+    CircleClose,
+    Radial,
+    Pixelize,
+    ZoomIn,
+}
+
+impl Transition {
+    /// Name of the transition in the xfade filter
+    pub fn filter_name(&self) -> &'static str {
+        match self {
+            Transition::Fade => "fade",
+            Transition::FadeBlack => "fadeblack",
+            Transition::FadeWhite => "fadewhite",
+            Transition::Dissolve => "dissolve",
+            Transition::WipeLeft => "wipeleft",
+            Transition::WipeRight => "wiperight",
+            Transition::WipeUp => "wipeup",
+            Transition::WipeDown => "wipedown",
+            Transition::SlideLeft => "slideleft",
+            Transition::SlideRight => "slideright",
+            Transition::SlideUp => "slideup",
+            Transition::SlideDown => "slidedown",
+            Transition::CircleOpen => "circleopen",
+            Transition::CircleClose => "circleclose",
+            Transition::Radial => "radial",
+            Transition::Pixelize => "pixelize",
+            Transition::ZoomIn => "zoomin",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum CaptionPosition {
+    Top,
+    Center,
+    Bottom,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum FrameFormat {
+    Png,
+    Jpg,
+    Webp,
+    Tiff,
+}
+
+impl FrameFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            FrameFormat::Png => "png",
+            FrameFormat::Jpg => "jpg",
+            FrameFormat::Webp => "webp",
+            FrameFormat::Tiff => "tiff",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum Dither {
+    Bayer,
+    Heckbert,
+    FloydSteinberg,
+    Sierra2,
+    Sierra2_4a,
+    Sierra3,
+    Burkes,
+    Atkinson,
+    None,
+}
+
+impl Dither {
+    /// Name of the dithering mode in the paletteuse filter
+    pub fn filter_name(&self) -> &'static str {
+        match self {
+            Dither::Bayer => "bayer",
+            Dither::Heckbert => "heckbert",
+            Dither::FloydSteinberg => "floyd_steinberg",
+            Dither::Sierra2 => "sierra2",
+            Dither::Sierra2_4a => "sierra2_4a",
+            Dither::Sierra3 => "sierra3",
+            Dither::Burkes => "burkes",
+            Dither::Atkinson => "atkinson",
+            Dither::None => "none",
+        }
+    }
+}
+
 #[derive(ValueEnum, Clone, Debug)]
 pub enum VideoEffect {
     Sepia,
@@ -19,6 +209,7 @@ pub enum VideoEffect {
     Vflip,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn cover_watermark(input: &str, output: &str, width: u32, height: u32, x: i32, y: i32, shape: Shape, color: &str) -> io::Result<()> {
     // Check if input file exists
     if !Path::new(input).exists() {
@@ -81,7 +272,7 @@ pub fn cover_watermark(input: &str, output: &str, width: u32, height: u32, x: i3
         println!("Watermark covered successfully!");
         Ok(())
     } else {
-        Err(io::Error::new(io::ErrorKind::Other, format!("FFmpeg command failed with status: {}", status)))
+        Err(io::Error::other(format!("FFmpeg command failed with status: {}", status)))
     }
 }
 
@@ -155,7 +346,7 @@ pub fn cross_fade_videos(input1: &str, input2: &str, output: &str, duration: f32
         println!("Videos cross-faded successfully!");
         Ok(())
     } else {
-        Err(io::Error::new(io::ErrorKind::Other, format!("FFmpeg command failed with status: {}", status)))
+        Err(io::Error::other(format!("FFmpeg command failed with status: {}", status)))
     }
 }
 
@@ -184,307 +375,862 @@ pub fn apply_effect(input: &str, output: &str, effect: &VideoEffect) -> io::Resu
     Ok(())
 }
 
-pub fn reverse_video(input: &str, output: &str) -> std::io::Result<()> {
-    let status = Command::new("ffmpeg")
-        .arg("-i")
-        .arg(input)
-        .arg("-vf")
-        .arg("reverse")
-        .arg("-af")
-        .arg("areverse")
-        .arg(output)
-        .status()
-        .expect("Failed to execute FFmpeg command");
+pub fn reverse_video(input: &str, output: &str, chunk: f64) -> std::io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+    if chunk <= 0.0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Chunk size must be greater than zero"));
+    }
 
-    if status.success() {
+    let duration = get_video_duration(input)?;
+    let (_, has_audio) = get_stream_info(input)?;
+
+    // Short clips fit in memory and can be reversed in one pass, encoded like the chunks below
+    if duration <= chunk {
+        let mut command = Command::new("ffmpeg");
+        command.arg("-i")
+               .arg(input)
+               .arg("-map")
+               .arg("0:v:0")
+               .arg("-vf")
+               .arg("reverse")
+               .arg("-c:v")
+               .arg("libx264")
+               .arg("-preset")
+               .arg("fast")
+               .arg("-crf")
+               .arg("18");
+        if has_audio {
+            command.arg("-map").arg("0:a:0")
+                   .arg("-af").arg("areverse")
+                   .arg("-c:a").arg("aac");
+        }
+        command.arg("-y") // Overwrite output file if it exists
+               .arg(output);
+        run_ffmpeg(&mut command)?;
         println!("Video reversed successfully!");
-    } else {
-        eprintln!("Error reversing video");
+        return Ok(());
     }
 
-    Ok(())
-}
-
-pub fn split_video(input: &str, output_dir: &str, parts: u32) -> io::Result<()> {
-    let output_path = Path::new(output_dir);
-    if !output_path.exists() {
-        std::fs::create_dir_all(output_path)?;
+    // The reverse filters buffer the whole clip, so reverse short chunks one at a time
+    // and join them back in reverse order. The temporary directory is removed on drop.
+    let temp_dir = tempfile::tempdir()?;
+    let mut count = (duration / chunk).ceil() as usize;
+    if count > 1 && duration - (count - 1) as f64 * chunk < 0.5 {
+        // Fold a tiny trailing remainder into the previous chunk
+        count -= 1;
     }
 
-    let output_pattern = output_path.join("part%03d.mp4").to_str().unwrap().to_string();
+    let mut chunk_files = Vec::new();
+    for index in 0..count {
+        let chunk_path = temp_dir.path().join(format!("chunk_{:04}.mkv", index));
 
-    // Get the duration of the input video
-    let duration = get_video_duration(input)?;
+        let mut command = Command::new("ffmpeg");
+        command.arg("-ss").arg((index as f64 * chunk).to_string());
 
-    // Calculate segment duration
-    let segment_duration = duration / parts as f64;
+        // The last chunk runs to the end of the input
+        if index + 1 < count {
+            command.arg("-t").arg(chunk.to_string());
+        }
 
-    let status = Command::new("ffmpeg")
-        .arg("-i")
-        .arg(input)
-        .arg("-f")
-        .arg("segment")
-        .arg("-segment_time")
-        .arg(segment_duration.to_string())
-        .arg("-reset_timestamps")
-        .arg("1")
-        .arg("-c")
-        .arg("copy")
-        .arg(&output_pattern)
-        .status()?;
+        command.arg("-i")
+               .arg(input)
+               .arg("-map")
+               .arg("0:v:0")
+               .arg("-vf")
+               .arg("reverse")
+               .arg("-c:v")
+               .arg("libx264")
+               .arg("-preset")
+               .arg("fast")
+               .arg("-crf")
+               .arg("18");
+
+        if has_audio {
+            command.arg("-map").arg("0:a:0")
+                   .arg("-af").arg("areverse")
+                   .arg("-c:a").arg("aac");
+        }
 
-    if status.success() {
-        println!("Video split successfully into {} parts!", parts);
-        Ok(())
-    } else {
-        Err(std::io::Error::new(std::io::ErrorKind::Other, "FFmpeg command failed"))
+        command.arg("-y").arg(&chunk_path);
+        run_ffmpeg(&mut command)?;
+        chunk_files.push(chunk_path);
     }
-}
 
-pub fn stretch_video(input: &str, output: &str, target_duration: f64) -> io::Result<()> {
-    // Check if input file exists
-    if !Path::new(input).exists() {
-        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    // The last chunk of the input becomes the first chunk of the output
+    let list_path = temp_dir.path().join("chunks.txt");
+    {
+        let mut file = File::create(&list_path)?;
+        for chunk_path in chunk_files.iter().rev() {
+            writeln!(file, "{}", concat_list_entry(chunk_path))?;
+        }
     }
 
-    // Get the duration of the input video
-    let original_duration = get_video_duration(input)?;
-
-    // Calculate the stretch factor
-    let stretch_factor = target_duration / original_duration;
+    let mut command = Command::new("ffmpeg");
+    command.arg("-f")
+           .arg("concat")
+           .arg("-safe")
+           .arg("0")
+           .arg("-i")
+           .arg(&list_path)
+           .arg("-c")
+           .arg("copy")
+           .arg("-y") // Overwrite output file if it exists
+           .arg(output);
 
-    // Construct the FFmpeg command
-    let output = Command::new("ffmpeg")
-        .arg("-i")
-        .arg(input)
-        .arg("-filter:v")
-        .arg(format!("setpts={}*PTS", stretch_factor))
-        .arg("-filter:a")
-        .arg(format!("atempo={}", 1.0 / stretch_factor))
-        .arg("-y") // Overwrite output file if it exists
-        .arg(output)
-        .output()?;
+    run_ffmpeg(&mut command)?;
 
-    if output.status.success() {
-        println!("Video stretched successfully from {:.2} seconds to {:.2} seconds!", original_duration, target_duration);
-        Ok(())
-    } else {
-        let error_message = String::from_utf8_lossy(&output.stderr);
-        Err(io::Error::new(io::ErrorKind::Other, format!("FFmpeg command failed: {}", error_message)))
-    }
+    println!("Video reversed successfully in {} chunks!", count);
+    Ok(())
 }
 
-pub fn trim_video(input: &str, output: &str, start: f32, end: f32) -> io::Result<()> {
+pub fn boomerang(input: &str, output: &str, loops: u32, speed: f64, audio: BoomerangAudio, chunk: f64) -> io::Result<()> {
     // Check if input file exists
     if !Path::new(input).exists() {
         return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
     }
+    if loops == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Loop count must be at least 1"));
+    }
+    if speed <= 0.0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Speed must be greater than zero"));
+    }
 
-    // Get video information
-    let (has_video, has_audio) = get_stream_info(input)?;
+    let (_, has_audio) = get_stream_info(input)?;
+    let keep_audio = has_audio && audio == BoomerangAudio::Reverse;
 
-    if !has_video {
-        return Err(io::Error::new(io::ErrorKind::Other, "Input file has no video stream"));
-    }
+    let temp_dir = tempfile::tempdir()?;
+    let forward = temp_dir.path().join("forward.mkv");
+    let inner = temp_dir.path().join("inner.mkv");
+    let backward = temp_dir.path().join("backward.mkv");
 
-    // Construct the FFmpeg command
+    // Forward pass with the speed factor applied
     let mut command = Command::new("ffmpeg");
     command.arg("-i")
            .arg(input)
+           .arg("-map")
+           .arg("0:v:0")
+           .arg("-vf")
+           .arg(format!("setpts=PTS/{}", speed))
+           .arg("-c:v")
+           .arg("libx264")
+           .arg("-preset")
+           .arg("fast")
+           .arg("-crf")
+           .arg("18");
+
+    if keep_audio {
+        command.arg("-map").arg("0:a:0")
+               .arg("-af").arg(atempo_chain(speed)?)
+               .arg("-c:a").arg("aac");
+    } else {
+        command.arg("-an");
+    }
+
+    command.arg("-y").arg(&forward);
+    run_ffmpeg(&mut command)?;
+
+    // Drop the first and last frame before reversing, so neither the turnaround
+    // nor the loop point shows the same frame twice
+    let frame_time = 1.0 / (get_frame_rate(input)? * speed);
+    let forward_duration = get_video_duration(forward.to_str().unwrap())?;
+
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i")
+           .arg(&forward)
            .arg("-ss")
-           .arg(start.to_string())
+           .arg(frame_time.to_string())
            .arg("-to")
-           .arg(end.to_string());
-
-    // Add video encoding options
-    command.arg("-map").arg("0:v");
-    
-    if has_audio {
-        command.arg("-map").arg("0:a");
+           .arg((forward_duration - frame_time).to_string())
+           .arg("-c:v")
+           .arg("libx264")
+           .arg("-preset")
+           .arg("fast")
+           .arg("-crf")
+           .arg("18");
+
+    if keep_audio {
+        command.arg("-c:a").arg("aac");
     }
 
-    // Force encoding to ensure output is not empty
-    command.arg("-c:v").arg("libx264")
-           .arg("-preset").arg("fast");
+    command.arg("-y").arg(&inner);
+    run_ffmpeg(&mut command)?;
 
-    if has_audio {
-        command.arg("-c:a").arg("aac");
+    reverse_video(inner.to_str().unwrap(), backward.to_str().unwrap(), chunk)?;
+
+    // Alternate forward and backward passes; both are encoded alike, so they can be joined without re-encoding
+    let list_path = temp_dir.path().join("parts.txt");
+    {
+        let mut file = File::create(&list_path)?;
+        for _ in 0..loops {
+            writeln!(file, "{}", concat_list_entry(&forward))?;
+            writeln!(file, "{}", concat_list_entry(&backward))?;
+        }
     }
 
-    command.arg("-y") // Overwrite output file if it exists
+    let mut command = Command::new("ffmpeg");
+    command.arg("-f")
+           .arg("concat")
+           .arg("-safe")
+           .arg("0")
+           .arg("-i")
+           .arg(&list_path)
+           .arg("-c")
+           .arg("copy")
+           .arg("-y") // Overwrite output file if it exists
            .arg(output);
 
-    // Print the command being run
-    println!("Running FFmpeg command: {:?}", command);
+    run_ffmpeg(&mut command)?;
 
-    // Run the command with piped stdout and stderr
-    let mut child = command.stdout(Stdio::piped())
-                           .stderr(Stdio::piped())
-                           .spawn()?;
+    println!("Boomerang created successfully with {} loop(s)!", loops);
+    Ok(())
+}
 
-    // Read stdout and stderr
-    let mut stdout = String::new();
-    let mut stderr = String::new();
-    if let Some(ref mut stdout_handle) = child.stdout {
-        stdout_handle.read_to_string(&mut stdout)?;
+pub fn split_video(input: &str, output_dir: &str, parts: u32) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
     }
-    if let Some(ref mut stderr_handle) = child.stderr {
-        stderr_handle.read_to_string(&mut stderr)?;
+
+    let output_path = Path::new(output_dir);
+    if !output_path.exists() {
+        std::fs::create_dir_all(output_path)?;
     }
 
-    // Wait for the command to finish and check the status
-    let status = child.wait()?;
+    // Keep the container of the input so every stream it carries can be copied
+    let extension = container_extension(input);
+    let output_pattern = output_path.join(format!("part%03d.{}", extension)).to_str().unwrap().to_string();
 
-    // Print stdout and stderr
-    println!("FFmpeg stdout:\n{}", stdout);
-    println!("FFmpeg stderr:\n{}", stderr);
+    // Get the duration of the input video
+    let duration = get_video_duration(input)?;
 
-    if status.success() {
-        println!("Video trimmed successfully!");
-        Ok(())
-    } else {
-        Err(io::Error::new(io::ErrorKind::Other, format!("FFmpeg command failed with status: {}", status)))
+    // Calculate segment duration
+    let segment_duration = duration / parts as f64;
+
+    // Phone recordings carry data tracks (timecode, sensor metadata) that most muxers
+    // refuse to copy, so keep every stream except those
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i")
+           .arg(input)
+           .arg("-map")
+           .arg("0")
+           .arg("-map")
+           .arg("-0:d")
+           .arg("-ignore_unknown")
+           .arg("-f")
+           .arg("segment")
+           .arg("-segment_time")
+           .arg(segment_duration.to_string())
+           .arg("-reset_timestamps")
+           .arg("1")
+           .arg("-c")
+           .arg("copy");
+
+    // Stream copy can only cut on keyframes, so have the muxer list what it actually produced.
+    let temp_dir = tempfile::tempdir()?;
+    let list_path = temp_dir.path().join("segments.txt");
+    command.arg("-segment_list")
+           .arg(&list_path)
+           .arg("-y") // Overwrite output files if they exist
+           .arg(&output_pattern);
+
+    run_ffmpeg(&mut command)?;
+
+    let files = read_segment_list(&list_path, output_path)?;
+    println!("Video split successfully into {} parts!", files.len());
+    report_durations(&files)
+}
+
+#[derive(Clone, Debug)]
+pub struct Chapter {
+    pub start: f64,
+    pub end: f64,
+    pub title: Option<String>,
+}
+
+pub fn split_by_chapters(input: &str, output_dir: &str) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+
+    let chapters = get_chapters(input)?;
+    if chapters.is_empty() {
+        return Err(io::Error::other(format!("No chapters found in {}", input)));
     }
+
+    let output_path = Path::new(output_dir);
+    if !output_path.exists() {
+        std::fs::create_dir_all(output_path)?;
+    }
+
+    // Keep the container of the input so every stream it carries can be copied
+    let extension = container_extension(input);
+    let mut files = Vec::new();
+
+    for (index, chapter) in chapters.iter().enumerate() {
+        // Name each part after its chapter, keeping the index so titles can repeat
+        let title = chapter.title.clone().unwrap_or_else(|| format!("Chapter {}", index + 1));
+        let file_name = format!("{:03}_{}.{}", index + 1, sanitize_filename(&title), extension);
+        let part_path = output_path.join(file_name);
+
+        // Copy every stream of the chapter range except data tracks the muxer cannot copy,
+        // carrying over the container metadata and tagging the part with its chapter title
+        let mut command = Command::new("ffmpeg");
+        command.arg("-ss")
+               .arg(chapter.start.to_string())
+               .arg("-i")
+               .arg(input)
+               .arg("-t")
+               .arg((chapter.end - chapter.start).to_string())
+               .arg("-map")
+               .arg("0")
+               .arg("-map")
+               .arg("-0:d")
+               .arg("-ignore_unknown")
+               .arg("-map_metadata")
+               .arg("0")
+               .arg("-map_chapters")
+               .arg("-1")
+               .arg("-metadata")
+               .arg(format!("title={}", title))
+               .arg("-c")
+               .arg("copy")
+               .arg("-avoid_negative_ts")
+               .arg("make_zero")
+               .arg("-y") // Overwrite output file if it exists
+               .arg(&part_path);
+
+        run_ffmpeg(&mut command)?;
+        files.push(part_path);
+    }
+
+    println!("Video split successfully into {} chapters!", chapters.len());
+    report_durations(&files)
 }
 
-pub fn get_stream_info(input: &str) -> io::Result<(bool, bool)> {
+pub fn get_chapters(input: &str) -> io::Result<Vec<Chapter>> {
     let output = Command::new("ffprobe")
-        .args(&["-v", "error", "-show_entries", "stream=codec_type", "-of", "csv=p=0", input])
+        .args(["-v", "error", "-show_entries", "chapter=start_time,end_time:chapter_tags=title", "-of", "default", input])
         .output()?;
 
-    if output.status.success() {
-        let streams = String::from_utf8_lossy(&output.stdout);
-        let has_video = streams.lines().any(|line| line == "video");
-        let has_audio = streams.lines().any(|line| line == "audio");
-        Ok((has_video, has_audio))
-    } else {
+    if !output.status.success() {
         let error_message = String::from_utf8_lossy(&output.stderr);
-        Err(io::Error::new(io::ErrorKind::Other, format!("FFprobe command failed: {}", error_message)))
+        return Err(io::Error::other(format!("FFprobe command failed: {}", error_message)));
     }
-}
 
-pub fn get_video_duration(input: &str) -> io::Result<f64> {
-    let output = Command::new("ffprobe")
-        .args(&["-v", "error", "-show_entries", "format=duration", "-of", "default=noprint_wrappers=1:nokey=1", input])
-        .output()?;
+    Ok(parse_chapters(&String::from_utf8_lossy(&output.stdout)))
+}
 
-    if output.status.success() {
-        let duration_str = String::from_utf8_lossy(&output.stdout);
-        duration_str.trim().parse::<f64>().map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
-    } else {
-        Err(std::io::Error::new(std::io::ErrorKind::Other, "FFprobe command failed"))
+fn parse_chapters(probe_output: &str) -> Vec<Chapter> {
+    // Each chapter is printed as a [CHAPTER] ... [/CHAPTER] block of key=value lines
+    let mut chapters = Vec::new();
+    let mut current: Option<Chapter> = None;
+    for line in probe_output.lines() {
+        let line = line.trim();
+        if line == "[CHAPTER]" {
+            current = Some(Chapter { start: 0.0, end: 0.0, title: None });
+        } else if line == "[/CHAPTER]" {
+            if let Some(chapter) = current.take() {
+                if chapter.end > chapter.start {
+                    chapters.push(chapter);
+                }
+            }
+        } else if let (Some(chapter), Some((key, value))) = (current.as_mut(), line.split_once('=')) {
+            match key {
+                "start_time" => chapter.start = value.parse().unwrap_or(0.0),
+                "end_time" => chapter.end = value.parse().unwrap_or(0.0),
+                "TAG:title" if !value.trim().is_empty() => chapter.title = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
     }
+
+    chapters
 }
 
-pub fn square_crop(input: &str, output: &str, size: Option<u32>, x_offset: Option<i32>, y_offset: Option<i32>) -> io::Result<()> {
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum ListFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Clone, Debug)]
+pub struct Scene {
+    pub start: f64,
+    pub end: f64,
+    /// Scene-change score of the cut that starts this scene (0 for the first scene)
+    pub score: f64,
+}
+
+pub fn list_scenes(input: &str, threshold: f64, min_length: f64, format: ListFormat, split_dir: Option<&str>) -> io::Result<()> {
     // Check if input file exists
     if !Path::new(input).exists() {
         return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
     }
 
-    // Get video dimensions
-    let (width, height) = get_video_dimensions(input)?;
-
-    // Determine the crop size (capped by the smaller dimension of the video)
-    let crop_size = min(size.unwrap_or_else(|| min(width, height)), min(width, height));
+    let scenes = detect_scenes(input, threshold, min_length)?;
 
-    // Calculate crop values
-    let x = x_offset.unwrap_or_else(|| ((width as i32 - crop_size as i32) / 2).max(0));
-    let y = y_offset.unwrap_or_else(|| ((height as i32 - crop_size as i32) / 2).max(0));
+    // Print the scene list
+    print!("{}", format_scene_list(&scenes, format));
 
-    // Ensure x and y are within the video dimensions
-    let x = max(0, min(x, width as i32 - crop_size as i32));
-    let y = max(0, min(y, height as i32 - crop_size as i32));
+    // Optionally cut the video into one clip per scene
+    if let Some(output_dir) = split_dir {
+        let cut_times: Vec<f64> = scenes.iter().skip(1).map(|scene| scene.start).collect();
+        let files = split_at_times(input, output_dir, &cut_times, "scene")?;
+        println!("Video split successfully into {} scenes!", files.len());
+        report_durations(&files)?;
+    }
 
-    // Construct the FFmpeg command
-    let mut command = Command::new("ffmpeg");
-    command.arg("-i")
-           .arg(input)
-           .arg("-vf")
-           .arg(format!("crop={}:{}:{}:{}", crop_size, crop_size, x, y))
-           .arg("-c:a")
-           .arg("copy")
-           .arg("-y") // Overwrite output file if it exists
-           .arg(output);
+    Ok(())
+}
 
-    // Print the command being run
-    println!("Running FFmpeg command: {:?}", command);
+pub fn detect_scenes(input: &str, threshold: f64, min_length: f64) -> io::Result<Vec<Scene>> {
+    let duration = get_video_duration(input)?;
 
-    // Run the command with piped stdout and stderr
-    let mut child = command.stdout(Stdio::piped())
-                           .stderr(Stdio::piped())
-                           .spawn()?;
+    // Keep only frames whose scene score exceeds the threshold and print their metadata
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-i", input, "-an", "-sn", "-dn"])
+        .arg("-vf")
+        .arg(format!("select='gt(scene,{})',metadata=print", threshold))
+        .args(["-f", "null", "-"])
+        .output()?;
 
-    // Read stdout and stderr
-    let mut stdout = String::new();
-    let mut stderr = String::new();
-    if let Some(ref mut stdout_handle) = child.stdout {
-        stdout_handle.read_to_string(&mut stdout)?;
+    if !output.status.success() {
+        let error_message = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(format!("FFmpeg command failed: {}", error_message)));
     }
-    if let Some(ref mut stderr_handle) = child.stderr {
-        stderr_handle.read_to_string(&mut stderr)?;
+
+    let cuts = parse_scene_cuts(&String::from_utf8_lossy(&output.stderr));
+    Ok(scenes_from_cuts(&cuts, duration, min_length))
+}
+
+fn parse_scene_cuts(log: &str) -> Vec<(f64, f64)> {
+    // The metadata filter logs a "pts_time:" line per selected frame, followed by its score
+    let mut cuts: Vec<(f64, f64)> = Vec::new();
+    for line in log.lines() {
+        if let Some(pos) = line.find("pts_time:") {
+            let time = line[pos + "pts_time:".len()..]
+                .split_whitespace()
+                .next()
+                .and_then(|value| value.parse::<f64>().ok());
+            if let Some(time) = time {
+                cuts.push((time, 0.0));
+            }
+        } else if let Some(pos) = line.find("lavfi.scene_score=") {
+            let score = line[pos + "lavfi.scene_score=".len()..].trim().parse::<f64>().unwrap_or(0.0);
+            if let Some(last) = cuts.last_mut() {
+                last.1 = score;
+            }
+        }
     }
 
-    // Wait for the command to finish and check the status
-    let status = child.wait()?;
+    cuts
+}
 
-    // Print stdout and stderr
-    println!("FFmpeg stdout:\n{}", stdout);
-    println!("FFmpeg stderr:\n{}", stderr);
+fn scenes_from_cuts(cuts: &[(f64, f64)], duration: f64, min_length: f64) -> Vec<Scene> {
+    // Drop cuts that would leave a scene shorter than the minimum length
+    let mut kept: Vec<(f64, f64)> = Vec::new();
+    let mut last_start = 0.0;
+    for &(time, score) in cuts {
+        if time - last_start >= min_length.max(f64::EPSILON) && duration - time >= min_length {
+            kept.push((time, score));
+            last_start = time;
+        }
+    }
 
-    if status.success() {
-        println!("Video cropped to square successfully!");
-        Ok(())
+    let mut scenes = Vec::new();
+    let mut start = 0.0;
+    let mut start_score = 0.0;
+    for (time, score) in kept {
+        scenes.push(Scene { start, end: time, score: start_score });
+        start = time;
+        start_score = score;
+    }
+    scenes.push(Scene { start, end: duration, score: start_score });
+
+    scenes
+}
+
+fn format_scene_list(scenes: &[Scene], format: ListFormat) -> String {
+    let mut list = String::new();
+    match format {
+        ListFormat::Text => {
+            for (index, scene) in scenes.iter().enumerate() {
+                list.push_str(&format!(
+                    "Scene {}: {} - {} ({:.3}s, score {:.3})\n",
+                    index + 1, format_timestamp(scene.start), format_timestamp(scene.end), scene.end - scene.start, scene.score
+                ));
+            }
+        }
+        ListFormat::Json => {
+            let entries: Vec<String> = scenes.iter().enumerate().map(|(index, scene)| {
+                format!(
+                    "  {{\"index\": {}, \"start\": {:.3}, \"end\": {:.3}, \"duration\": {:.3}, \"score\": {:.3}}}",
+                    index + 1, scene.start, scene.end, scene.end - scene.start, scene.score
+                )
+            }).collect();
+            list.push_str(&format!("[\n{}\n]\n", entries.join(",\n")));
+        }
+        ListFormat::Csv => {
+            list.push_str("index,start,end,duration,score\n");
+            for (index, scene) in scenes.iter().enumerate() {
+                list.push_str(&format!(
+                    "{},{:.3},{:.3},{:.3},{:.3}\n",
+                    index + 1, scene.start, scene.end, scene.end - scene.start, scene.score
+                ));
+            }
+        }
+    }
+    list
+}
+
+pub fn split_at_times(input: &str, output_dir: &str, cut_times: &[f64], prefix: &str) -> io::Result<Vec<PathBuf>> {
+    let output_path = Path::new(output_dir);
+    if !output_path.exists() {
+        std::fs::create_dir_all(output_path)?;
+    }
+
+    // Keep the container of the input
+    let extension = container_extension(input);
+    let output_pattern = output_path.join(format!("{}%03d.{}", prefix, extension)).to_str().unwrap().to_string();
+
+    let (_, has_audio) = get_stream_info(input)?;
+
+    // Re-encode with keyframes forced at the cut points so every clip starts exactly on its cut,
+    // copying subtitle tracks along with the video and audio
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i")
+           .arg(input)
+           .arg("-map")
+           .arg("0:v:0")
+           .arg("-map")
+           .arg("0:s?");
+
+    if has_audio {
+        command.arg("-map").arg("0:a");
+    }
+
+    // WebM only accepts VP8/VP9/AV1 video and Vorbis/Opus audio
+    let (video_codec, audio_codec) = if extension == "webm" {
+        ("libvpx-vp9", "libopus")
+    } else {
+        ("libx264", "aac")
+    };
+
+    command.arg("-c:v").arg(video_codec)
+           .arg("-c:s").arg("copy");
+
+    if video_codec == "libx264" {
+        command.arg("-preset").arg("fast");
+    }
+
+    if has_audio {
+        command.arg("-c:a").arg(audio_codec);
+    }
+
+    command.arg("-f").arg("segment")
+           .arg("-reset_timestamps").arg("1");
+
+    if !cut_times.is_empty() {
+        let times: Vec<String> = cut_times.iter().map(|time| format!("{:.3}", time)).collect();
+        command.arg("-force_key_frames").arg(times.join(","))
+               .arg("-segment_times").arg(times.join(","));
     } else {
-        Err(io::Error::new(io::ErrorKind::Other, format!("FFmpeg command failed with status: {}", status)))
+        // A single clip: make the segment longer than the video
+        command.arg("-segment_time").arg((get_video_duration(input)? + 1.0).to_string());
     }
+
+    // Have the muxer list the clips it wrote, so files left over from earlier runs are not picked up.
+    let temp_dir = tempfile::tempdir()?;
+    let list_path = temp_dir.path().join("segments.txt");
+    command.arg("-segment_list")
+           .arg(&list_path)
+           .arg("-y") // Overwrite output files if they exist
+           .arg(&output_pattern);
+
+    run_ffmpeg(&mut command)?;
+
+    read_segment_list(&list_path, output_path)
 }
 
-pub fn get_video_dimensions(input: &str) -> io::Result<(u32, u32)> {
-    let output = Command::new("ffprobe")
-        .args(&["-v", "error", "-select_streams", "v:0", "-count_packets", "-show_entries", "stream=width,height", "-of", "csv=p=0", input])
+pub fn stretch_video(input: &str, output: &str, target_duration: f64, pitch: PitchMode, interpolation: Option<Interpolation>, fps: Option<f64>) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+
+    if !target_duration.is_finite() || target_duration <= 0.0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Target duration must be greater than zero"));
+    }
+
+    // Get the duration of the input video
+    let original_duration = get_video_duration(input)?;
+    if original_duration <= 0.0 {
+        return Err(io::Error::other(format!("Could not determine the duration of {}", input)));
+    }
+
+    // Calculate the stretch factor
+    let stretch_factor = target_duration / original_duration;
+
+    // Only build an audio filter when there is audio to filter
+    let (_, has_audio) = get_stream_info(input)?;
+    let audio_filter = match pitch {
+        _ if !has_audio => None,
+        PitchMode::DropAudio => None,
+        PitchMode::Preserve => Some(atempo_chain(1.0 / stretch_factor)?),
+        PitchMode::Shift => {
+            let sample_rate = get_audio_sample_rate(input)?;
+            Some(format!("asetrate={},aresample={}", (sample_rate as f64 / stretch_factor).round(), sample_rate))
+        }
+    };
+
+    // Synthesize intermediate frames at the target frame rate instead of duplicating frames
+    let mut video_filter = format!("setpts={}*PTS", stretch_factor);
+    if let Some(interpolation) = interpolation {
+        let fps = match fps {
+            Some(fps) => fps,
+            None => get_frame_rate(input)?,
+        };
+        match interpolation {
+            Interpolation::Motion => video_filter.push_str(&format!(
+                ",minterpolate=fps={}:mi_mode=mci:mc_mode=aobmc:me_mode=bidir:vsbmc=1", fps
+            )),
+            Interpolation::Blend => video_filter.push_str(&format!(",framerate=fps={}", fps)),
+        }
+    }
+
+    // Construct the FFmpeg command
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i")
+           .arg(input)
+           .arg("-filter:v")
+           .arg(&video_filter);
+
+    match audio_filter {
+        Some(filter) => {
+            command.arg("-filter:a").arg(filter);
+        }
+        None => {
+            command.arg("-an");
+        }
+    }
+
+    let output = command.arg("-y") // Overwrite output file if it exists
+        .arg(output)
         .output()?;
 
     if output.status.success() {
-        let dimensions = String::from_utf8_lossy(&output.stdout);
-        let mut parts = dimensions.trim().split(',');
-        let width = parts.next().unwrap_or("0").parse::<u32>().unwrap_or(0);
-        let height = parts.next().unwrap_or("0").parse::<u32>().unwrap_or(0);
-        Ok((width, height))
+        println!("Video stretched successfully from {:.2} seconds to {:.2} seconds!", original_duration, target_duration);
+        Ok(())
     } else {
         let error_message = String::from_utf8_lossy(&output.stderr);
-        Err(io::Error::new(io::ErrorKind::Other, format!("FFprobe command failed: {}", error_message)))
+        Err(io::Error::other(format!("FFmpeg command failed: {}", error_message)))
     }
 }
 
-pub fn concatenate_videos(inputs: &[String], output: &str) -> io::Result<()> {
-    // Check if input files exist
-    for input in inputs {
-        if !Path::new(input).exists() {
-            return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+fn atempo_chain(tempo: f64) -> io::Result<String> {
+    if !tempo.is_finite() || tempo <= 0.0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid audio tempo: {}", tempo)));
+    }
+
+    // atempo only accepts factors between 0.5 and 2.0 on older builds,
+    // so larger changes are split into a chain of filters that ends with the remaining factor
+    let mut remaining = tempo;
+    let mut filters = Vec::new();
+    while remaining >= 2.0 {
+        filters.push("atempo=2.0".to_string());
+        remaining /= 2.0;
+    }
+    while remaining <= 0.5 {
+        filters.push("atempo=0.5".to_string());
+        remaining /= 0.5;
+    }
+    filters.push(format!("atempo={:.6}", remaining));
+    Ok(filters.join(","))
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SpeedKeyframe {
+    pub time: f64,
+    pub speed: f64,
+}
+
+pub fn parse_speed_keyframe(value: &str) -> Result<SpeedKeyframe, String> {
+    // Keyframes are written as TIME:SPEED, e.g. "7:4" or "7:4x"
+    let (time, speed) = value.split_once(':').ok_or_else(|| format!("Invalid keyframe '{}', expected TIME:SPEED", value))?;
+    let time = time.trim().parse::<f64>().map_err(|e| format!("Invalid keyframe time '{}': {}", time, e))?;
+    let speed = speed.trim().trim_end_matches(['x', 'X']).parse::<f64>().map_err(|e| format!("Invalid keyframe speed '{}': {}", speed, e))?;
+    if !time.is_finite() || time < 0.0 {
+        return Err(format!("Keyframe time must be a finite, non-negative number: {}", value));
+    }
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err(format!("Keyframe speed must be a finite number greater than zero: {}", value));
+    }
+    Ok(SpeedKeyframe { time, speed })
+}
+
+pub fn speed_ramp(input: &str, output: &str, keyframes: &[SpeedKeyframe], step: f64) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+    if keyframes.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "At least one speed keyframe is required"));
+    }
+    if step <= 0.0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Ramp step must be greater than zero"));
+    }
+
+    let mut keyframes = keyframes.to_vec();
+    keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+    let duration = get_video_duration(input)?;
+    let (_, has_audio) = get_stream_info(input)?;
+
+    // Approximate the speed curve with constant-speed segments
+    let segments = speed_segments(&keyframes, duration, step);
+    let new_duration: f64 = segments.iter().map(|(start, end, speed)| (end - start) / speed).sum();
+
+    // Trim each segment out of the input, retime it and join everything back together
+    let count = segments.len();
+    let mut filter_complex = String::new();
+    filter_complex.push_str(&format!("[0:v]split={}{};", count, (0..count).map(|i| format!("[vs{}]", i)).collect::<String>()));
+    if has_audio {
+        filter_complex.push_str(&format!("[0:a]asplit={}{};", count, (0..count).map(|i| format!("[as{}]", i)).collect::<String>()));
+    }
+
+    let mut concat_inputs = String::new();
+    for (i, (start, end, speed)) in segments.iter().enumerate() {
+        filter_complex.push_str(&format!(
+            "[vs{i}]trim=start={start:.6}:end={end:.6},setpts=(PTS-STARTPTS)/{speed:.6}[v{i}];"
+        ));
+        concat_inputs.push_str(&format!("[v{}]", i));
+        if has_audio {
+            filter_complex.push_str(&format!(
+                "[as{i}]atrim=start={start:.6}:end={end:.6},asetpts=PTS-STARTPTS,{}[a{i}];",
+                atempo_chain(*speed)?
+            ));
+            concat_inputs.push_str(&format!("[a{}]", i));
         }
     }
+    filter_complex.push_str(&format!("{}concat=n={}:v=1:a={}[v]", concat_inputs, count, if has_audio { 1 } else { 0 }));
+    if has_audio {
+        filter_complex.push_str("[a]");
+    }
 
-    // Create a temporary file list
-    let temp_file = "temp_file_list.txt";
-    {
-        let mut file = File::create(temp_file)?;
-        for input in inputs {
-            writeln!(file, "file '{}'", input)?;
+    // Construct the FFmpeg command
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i")
+           .arg(input)
+           .arg("-filter_complex")
+           .arg(&filter_complex)
+           .arg("-map")
+           .arg("[v]");
+
+    if has_audio {
+        command.arg("-map").arg("[a]");
+    }
+
+    command.arg("-y") // Overwrite output file if it exists
+           .arg(output);
+
+    run_ffmpeg(&mut command)?;
+
+    println!("Speed ramp applied successfully! Duration changed from {:.2} seconds to {:.2} seconds.", duration, new_duration);
+    Ok(())
+}
+
+fn speed_segments(keyframes: &[SpeedKeyframe], duration: f64, step: f64) -> Vec<(f64, f64, f64)> {
+    // Speed is held before the first and after the last keyframe and interpolated linearly in between
+    let speed_at = |time: f64| -> f64 {
+        let first = keyframes[0];
+        let last = keyframes[keyframes.len() - 1];
+        if time <= first.time {
+            return first.speed;
+        }
+        if time >= last.time {
+            return last.speed;
+        }
+        let pair = keyframes.windows(2).find(|pair| time <= pair[1].time).unwrap();
+        let (a, b) = (pair[0], pair[1]);
+        if b.time - a.time <= f64::EPSILON {
+            return b.speed;
+        }
+        a.speed + (b.speed - a.speed) * (time - a.time) / (b.time - a.time)
+    };
+
+    // Split the timeline at every keyframe, then subdivide ramps into steps
+    let mut boundaries = vec![0.0];
+    for keyframe in keyframes {
+        if keyframe.time > 0.0 && keyframe.time < duration {
+            boundaries.push(keyframe.time);
+        }
+    }
+    boundaries.push(duration);
+    boundaries.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+
+    let mut segments: Vec<(f64, f64, f64)> = Vec::new();
+    for pair in boundaries.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        let ramps = (speed_at(start) - speed_at(end)).abs() > f64::EPSILON;
+        let steps = if ramps { ((end - start) / step).ceil().max(1.0) as usize } else { 1 };
+        for i in 0..steps {
+            let a = start + (end - start) * i as f64 / steps as f64;
+            let b = start + (end - start) * (i + 1) as f64 / steps as f64;
+            let speed = speed_at((a + b) / 2.0);
+
+            // Merge with the previous segment when the speed does not change
+            match segments.last_mut() {
+                Some(last) if (last.2 - speed).abs() < 1e-9 => last.1 = b,
+                _ => segments.push((a, b, speed)),
+            }
         }
     }
+    segments
+}
+
+pub fn trim_video(input: &str, output: &str, start: f32, end: f32) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+
+    // Get video information
+    let (has_video, has_audio) = get_stream_info(input)?;
+
+    if !has_video {
+        return Err(io::Error::other("Input file has no video stream"));
+    }
 
     // Construct the FFmpeg command
     let mut command = Command::new("ffmpeg");
-    command.arg("-f")
-           .arg("concat")
-           .arg("-safe")
-           .arg("0")
-           .arg("-i")
-           .arg(temp_file)
-           .arg("-c")
-           .arg("copy")
-           .arg("-y") // Overwrite output file if it exists
+    command.arg("-i")
+           .arg(input)
+           .arg("-ss")
+           .arg(start.to_string())
+           .arg("-to")
+           .arg(end.to_string());
+
+    // Add video encoding options
+    command.arg("-map").arg("0:v");
+    
+    if has_audio {
+        command.arg("-map").arg("0:a");
+    }
+
+    // Force encoding to ensure output is not empty
+    command.arg("-c:v").arg("libx264")
+           .arg("-preset").arg("fast");
+
+    if has_audio {
+        command.arg("-c:a").arg("aac");
+    }
+
+    command.arg("-y") // Overwrite output file if it exists
            .arg(output);
 
     // Print the command being run
@@ -512,70 +1258,2806 @@ pub fn concatenate_videos(inputs: &[String], output: &str) -> io::Result<()> {
     println!("FFmpeg stdout:\n{}", stdout);
     println!("FFmpeg stderr:\n{}", stderr);
 
-    // Remove the temporary file
-    std::fs::remove_file(temp_file)?;
-
     if status.success() {
-        println!("Videos concatenated successfully!");
+        println!("Video trimmed successfully!");
         Ok(())
     } else {
-        Err(io::Error::new(io::ErrorKind::Other, format!("FFmpeg command failed with status: {}", status)))
+        Err(io::Error::other(format!("FFmpeg command failed with status: {}", status)))
     }
 }
 
-pub fn create_slideshow(input_folder: &str, output: &str, duration: u32) -> Result<(), Box<dyn std::error::Error>> {
-    let current_dir = std::env::current_dir()?;
-    let input_path = make_absolute_path(input_folder, &current_dir);
-    let output_path = make_absolute_path(output, &current_dir);
-
-    if !input_path.is_dir() {
-        return Err(format!("{} is not a directory", input_path.display()).into());
-    }
-
-    // Create a temporary file to store the list of images
-    let temp_file = tempfile::NamedTempFile::new()?;
-    let temp_path = temp_file.path().to_str().unwrap();
+pub fn get_stream_info(input: &str) -> io::Result<(bool, bool)> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries", "stream=codec_type", "-of", "csv=p=0", input])
+        .output()?;
 
-    // Write the list of image files to the temporary file
-    let mut image_list = String::new();
-    for entry in std::fs::read_dir(&input_path)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_file() && is_image_file(&path) {
-            image_list.push_str(&format!("file '{}'\nduration {}\n", path.display(), duration));
-        }
+    if output.status.success() {
+        let streams = String::from_utf8_lossy(&output.stdout);
+        let has_video = streams.lines().any(|line| line == "video");
+        let has_audio = streams.lines().any(|line| line == "audio");
+        Ok((has_video, has_audio))
+    } else {
+        let error_message = String::from_utf8_lossy(&output.stderr);
+        Err(io::Error::other(format!("FFprobe command failed: {}", error_message)))
     }
-    std::fs::write(temp_path, image_list)?;
+}
 
-    // Construct the FFmpeg command
-    let output = Command::new("ffmpeg")
-        .args(&[
-            "-f", "concat",
-            "-safe", "0",
-            "-i", temp_path,
-            "-vsync", "vfr",
-            "-pix_fmt", "yuv420p",
-            output_path.to_str().unwrap(),
-        ])
+pub fn get_codecs(input: &str) -> io::Result<(Option<String>, Option<String>)> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries", "stream=codec_name,codec_type", "-of", "csv=p=0", input])
         .output()?;
 
     if !output.status.success() {
-        return Err(format!("FFmpeg command failed: {}", String::from_utf8_lossy(&output.stderr)).into());
+        let error_message = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(format!("FFprobe command failed: {}", error_message)));
     }
 
-    Ok(())
+    // One "codec,type" line per stream; the first video and audio streams are reported
+    let streams = String::from_utf8_lossy(&output.stdout);
+    let codec_of = |kind: &str| {
+        streams.lines()
+            .filter_map(|line| line.split_once(','))
+            .find(|(_, codec_type)| codec_type.trim() == kind)
+            .map(|(codec, _)| codec.trim().to_string())
+    };
+    Ok((codec_of("video"), codec_of("audio")))
 }
 
-fn make_absolute_path(path: &str, current_dir: &Path) -> PathBuf {
-    let path_buf = PathBuf::from(path);
-    if path_buf.is_absolute() {
-        path_buf
+pub fn get_audio_sample_rate(input: &str) -> io::Result<u32> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "a:0", "-show_entries", "stream=sample_rate", "-of", "default=noprint_wrappers=1:nokey=1", input])
+        .output()?;
+
+    if output.status.success() {
+        let sample_rate = String::from_utf8_lossy(&output.stdout);
+        sample_rate.trim().parse::<u32>().map_err(io::Error::other)
     } else {
-        current_dir.join(path_buf)
+        let error_message = String::from_utf8_lossy(&output.stderr);
+        Err(io::Error::other(format!("FFprobe command failed: {}", error_message)))
+    }
+}
+
+pub fn get_frame_rate(input: &str) -> io::Result<f64> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0", "-show_entries", "stream=r_frame_rate", "-of", "default=noprint_wrappers=1:nokey=1", input])
+        .output()?;
+
+    if !output.status.success() {
+        let error_message = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(format!("FFprobe command failed: {}", error_message)));
+    }
+
+    // The frame rate is reported as a fraction, e.g. "30000/1001"
+    let rate = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let parsed = match rate.split_once('/') {
+        Some((num, den)) => match (num.parse::<f64>(), den.parse::<f64>()) {
+            (Ok(num), Ok(den)) if den > 0.0 => Some(num / den),
+            _ => None,
+        },
+        None => rate.parse::<f64>().ok(),
+    };
+
+    match parsed {
+        Some(fps) if fps > 0.0 => Ok(fps),
+        _ => Err(io::Error::other(format!("Could not determine frame rate of {}", input))),
+    }
+}
+
+pub fn get_video_duration(input: &str) -> io::Result<f64> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries", "format=duration", "-of", "default=noprint_wrappers=1:nokey=1", input])
+        .output()?;
+
+    if output.status.success() {
+        let duration_str = String::from_utf8_lossy(&output.stdout);
+        duration_str.trim().parse::<f64>().map_err(std::io::Error::other)
+    } else {
+        Err(std::io::Error::other("FFprobe command failed"))
+    }
+}
+
+pub fn square_crop(input: &str, output: &str, size: Option<u32>, x_offset: Option<i32>, y_offset: Option<i32>) -> io::Result<()> {
+    // A square crop is a crop to 1:1, or to an explicit square size
+    let target = match size {
+        Some(size) => CropTarget::Size(size, size),
+        None => CropTarget::Aspect(1, 1),
+    };
+    crop_video(input, output, Some(target), Anchor::Center, x_offset, y_offset, false)
+}
+
+pub fn crop_video(input: &str, output: &str, target: Option<CropTarget>, anchor: Anchor, x_offset: Option<i32>, y_offset: Option<i32>, auto: bool) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+
+    // Start from the detected picture area when removing black bars, otherwise the full frame
+    let (area_width, area_height, area_x, area_y) = if auto {
+        let area = detect_crop(input)?;
+        println!("Detected picture area: crop={}:{}:{}:{}", area.0, area.1, area.2, area.3);
+        area
+    } else {
+        let (width, height) = get_video_dimensions(input)?;
+        (width, height, 0, 0)
+    };
+
+    // Crop to the target inside that area, or keep the whole area
+    let (crop_width, crop_height, x, y) = match target {
+        Some(target) => {
+            let (w, h, x, y) = crop_rect(area_width, area_height, target, anchor, x_offset, y_offset);
+            (w, h, x + area_x as i32, y + area_y as i32)
+        }
+        None => (area_width, area_height, area_x as i32, area_y as i32),
+    };
+
+    // Construct the FFmpeg command
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i")
+           .arg(input)
+           .arg("-vf")
+           .arg(format!("crop={}:{}:{}:{}", crop_width, crop_height, x, y))
+           .arg("-c:a")
+           .arg("copy")
+           .arg("-y") // Overwrite output file if it exists
+           .arg(output);
+
+    run_ffmpeg(&mut command)?;
+
+    println!("Video cropped successfully to {}x{} at {},{}!", crop_width, crop_height, x, y);
+    Ok(())
+}
+
+pub fn reframe_video(input: &str, output: &str, aspect: (u32, u32), threshold: f64, smoothing: f64) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+
+    let (width, height) = get_video_dimensions(input)?;
+    if width == 0 || height == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Input file has no video stream: {}", input)));
+    }
+    let (crop_width, crop_height, _, _) = crop_rect(width, height, CropTarget::Aspect(aspect.0, aspect.1), Anchor::Center, None, None);
+
+    // Shots are reframed independently so the crop cuts along with the video instead of panning across a cut
+    let shots = detect_scenes(input, threshold, 1.0)?;
+    let samples = motion_centers(input, width, height)?;
+
+    // Place a keyframe for the crop position every half second of each shot
+    let max_x = width.saturating_sub(crop_width) as f64;
+    let max_y = height.saturating_sub(crop_height) as f64;
+    let mut commands = String::new();
+    let mut first_position = None;
+
+    for shot in &shots {
+        let shot_samples: Vec<&(f64, f64, f64, f64)> = samples.iter().filter(|s| s.0 >= shot.start && s.0 < shot.end).collect();
+        let shot_center = weighted_center(&shot_samples).unwrap_or((0.5, 0.5));
+
+        let mut keyframes = Vec::new();
+        let mut time = shot.start;
+        loop {
+            // Average the motion around this point in time, weighted by its energy
+            let window: Vec<&(f64, f64, f64, f64)> = shot_samples.iter()
+                .filter(|s| (s.0 - time).abs() <= smoothing / 2.0)
+                .copied()
+                .collect();
+            let (center_x, center_y) = weighted_center(&window).unwrap_or(shot_center);
+            let x = (center_x * width as f64 - crop_width as f64 / 2.0).clamp(0.0, max_x);
+            let y = (center_y * height as f64 - crop_height as f64 / 2.0).clamp(0.0, max_y);
+            keyframes.push((time, x, y));
+
+            if time >= shot.end {
+                break;
+            }
+            time = f64::min(time + 0.5, shot.end);
+        }
+
+        if first_position.is_none() {
+            first_position = keyframes.first().map(|&(_, x, y)| (x, y));
+        }
+
+        // Pan linearly between keyframes; each interval sets the expression crop evaluates per frame
+        for pair in keyframes.windows(2) {
+            let ((t0, x0, y0), (t1, x1, y1)) = (pair[0], pair[1]);
+            let duration = (t1 - t0).max(0.001);
+            commands.push_str(&format!(
+                "{t0:.3} crop x '{x0:.1}+({:.3})*(t-{t0:.3})/{duration:.3}', crop y '{y0:.1}+({:.3})*(t-{t0:.3})/{duration:.3}';\n",
+                x1 - x0, y1 - y0
+            ));
+        }
+    }
+
+    // Write the crop commands to a file for sendcmd
+    let temp_dir = tempfile::tempdir()?;
+    let commands_path = temp_dir.path().join("reframe.cmd");
+    std::fs::write(&commands_path, commands)?;
+
+    let (x, y) = first_position.unwrap_or((max_x / 2.0, max_y / 2.0));
+    let filter = format!(
+        "sendcmd=f='{}',crop={}:{}:{:.0}:{:.0}",
+        commands_path.display(), crop_width, crop_height, x, y
+    );
+
+    // Construct the FFmpeg command
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i")
+           .arg(input)
+           .arg("-vf")
+           .arg(&filter)
+           .arg("-c:a")
+           .arg("copy")
+           .arg("-y") // Overwrite output file if it exists
+           .arg(output);
+
+    run_ffmpeg(&mut command)?;
+
+    println!("Video reframed successfully to {}x{} across {} shot(s)!", crop_width, crop_height, shots.len());
+    Ok(())
+}
+
+fn motion_centers(input: &str, width: u32, height: u32) -> io::Result<Vec<(f64, f64, f64, f64)>> {
+    // Analyse a small grayscale copy of the video, a few frames per second
+    const ANALYSIS_FPS: f64 = 5.0;
+    const ANALYSIS_WIDTH: usize = 64;
+    let analysis_height = ((ANALYSIS_WIDTH as f64 * height as f64 / width.max(1) as f64).round() as usize).max(2);
+
+    // Difference consecutive frames so only moving content carries energy
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-i", input, "-an", "-sn", "-dn"])
+        .arg("-vf")
+        .arg(format!(
+            "fps={},scale={}:{},format=gray,tblend=all_mode=difference",
+            ANALYSIS_FPS, ANALYSIS_WIDTH, analysis_height
+        ))
+        .args(["-f", "rawvideo", "-pix_fmt", "gray", "-"])
+        .output()?;
+
+    if !output.status.success() {
+        let error_message = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(format!("FFmpeg command failed: {}", error_message)));
+    }
+
+    // Each sample is (time, center x, center y, energy) with the center relative to the frame size
+    let frame_size = ANALYSIS_WIDTH * analysis_height;
+    let samples = output.stdout
+        .chunks_exact(frame_size)
+        .enumerate()
+        .map(|(index, frame)| {
+            let mut energy = 0.0;
+            let mut sum_x = 0.0;
+            let mut sum_y = 0.0;
+            for (pixel, &value) in frame.iter().enumerate() {
+                let value = value as f64;
+                energy += value;
+                sum_x += value * ((pixel % ANALYSIS_WIDTH) as f64 + 0.5);
+                sum_y += value * ((pixel / ANALYSIS_WIDTH) as f64 + 0.5);
+            }
+            let time = (index + 1) as f64 / ANALYSIS_FPS;
+            if energy > 0.0 {
+                (time, sum_x / energy / ANALYSIS_WIDTH as f64, sum_y / energy / analysis_height as f64, energy)
+            } else {
+                (time, 0.5, 0.5, 0.0)
+            }
+        })
+        .collect();
+
+    Ok(samples)
+}
+
+fn weighted_center(samples: &[&(f64, f64, f64, f64)]) -> Option<(f64, f64)> {
+    // Energy-weighted average of the motion centers, None when nothing moves
+    let energy: f64 = samples.iter().map(|s| s.3).sum();
+    if energy <= 0.0 {
+        return None;
+    }
+    let x = samples.iter().map(|s| s.1 * s.3).sum::<f64>() / energy;
+    let y = samples.iter().map(|s| s.2 * s.3).sum::<f64>() / energy;
+    Some((x, y))
+}
+
+pub fn fit_video(input: &str, output: &str, canvas: (u32, u32), background: FitBackground, color: &str, image: Option<&str>, blur: u32) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+
+    let (width, height) = (even(canvas.0), even(canvas.1));
+
+    // Scale the video to fit inside the canvas and fill the rest with the chosen background
+    let foreground = format!("scale={}:{}:force_original_aspect_ratio=decrease:force_divisible_by=2,setsar=1", width, height);
+    let cover = format!("scale={}:{}:force_original_aspect_ratio=increase,crop={}:{},setsar=1", width, height, width, height);
+
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i").arg(input);
+
+    let filter_complex = match background {
+        FitBackground::Blur => format!(
+            "[0:v]split=2[bg][fg];[bg]{},boxblur={}:2[blurred];[fg]{}[scaled];[blurred][scaled]overlay=(W-w)/2:(H-h)/2,format=yuv420p[v]",
+            cover, blur, foreground
+        ),
+        FitBackground::Color => format!(
+            "[0:v]{},pad={}:{}:(ow-iw)/2:(oh-ih)/2:color={},format=yuv420p[v]",
+            foreground, width, height, color
+        ),
+        FitBackground::Image => {
+            let image = image.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "A background image is required"))?;
+            if !Path::new(image).exists() {
+                return Err(io::Error::new(io::ErrorKind::NotFound, format!("Background image not found: {}", image)));
+            }
+            command.arg("-loop").arg("1").arg("-i").arg(image);
+            format!(
+                "[1:v]{}[bg];[0:v]{}[scaled];[bg][scaled]overlay=(W-w)/2:(H-h)/2:shortest=1,format=yuv420p[v]",
+                cover, foreground
+            )
+        }
+    };
+
+    command.arg("-filter_complex")
+           .arg(&filter_complex)
+           .arg("-map")
+           .arg("[v]")
+           .arg("-map")
+           .arg("0:a?")
+           .arg("-c:a")
+           .arg("copy")
+           .arg("-y") // Overwrite output file if it exists
+           .arg(output);
+
+    run_ffmpeg(&mut command)?;
+
+    println!("Video fitted successfully into a {}x{} canvas!", width, height);
+    Ok(())
+}
+
+pub fn detect_crop(input: &str) -> io::Result<(u32, u32, u32, u32)> {
+    let duration = get_video_duration(input)?;
+
+    // Sample a few short segments spread over the video, skipping the very start and end
+    // where fades to black would make the detected area too small
+    let samples = if duration > 60.0 { 6 } else { 3 };
+    let sample_length = f64::min(2.0, duration / samples as f64);
+    let mut counts: Vec<((u32, u32, u32, u32), usize)> = Vec::new();
+
+    for index in 0..samples {
+        let start = duration * (index as f64 + 0.5) / samples as f64 - sample_length / 2.0;
+
+        let output = Command::new("ffmpeg")
+            .args(["-hide_banner", "-nostats"])
+            .arg("-ss")
+            .arg(start.max(0.0).to_string())
+            .arg("-i")
+            .arg(input)
+            .arg("-t")
+            .arg(sample_length.to_string())
+            .args(["-an", "-sn", "-dn", "-vf", "cropdetect=limit=24:round=2", "-f", "null", "-"])
+            .output()?;
+
+        if !output.status.success() {
+            let error_message = String::from_utf8_lossy(&output.stderr);
+            return Err(io::Error::other(format!("FFmpeg command failed: {}", error_message)));
+        }
+
+        // cropdetect logs a suggested "crop=W:H:X:Y" for every frame
+        for line in String::from_utf8_lossy(&output.stderr).lines() {
+            let Some(pos) = line.rfind("crop=") else { continue };
+            let values: Vec<u32> = line[pos + "crop=".len()..]
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .split(':')
+                .filter_map(|value| value.parse::<u32>().ok())
+                .collect();
+            if let [w, h, x, y] = values[..] {
+                if w == 0 || h == 0 {
+                    continue;
+                }
+                match counts.iter_mut().find(|(rect, _)| *rect == (w, h, x, y)) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push(((w, h, x, y), 1)),
+                }
+            }
+        }
+    }
+
+    // The rectangle suggested for the most frames is the most stable one.
+    // Sizes are rounded to even values of at least 2; offsets may be 0, so they are only rounded down.
+    counts.into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|((w, h, x, y), _)| (even(w), even(h), x & !1, y & !1))
+        .ok_or_else(|| io::Error::other(format!("Could not detect a crop area for {}", input)))
+}
+
+fn crop_rect(width: u32, height: u32, target: CropTarget, anchor: Anchor, x_offset: Option<i32>, y_offset: Option<i32>) -> (u32, u32, i32, i32) {
+    // Determine the crop size, capped to fit inside the video while keeping its shape
+    let (crop_width, crop_height) = match target {
+        CropTarget::Aspect(aspect_width, aspect_height) => {
+            let fitted_height = (width as u64 * aspect_height as u64 / aspect_width as u64) as u32;
+            if fitted_height <= height {
+                (width, fitted_height)
+            } else {
+                ((height as u64 * aspect_width as u64 / aspect_height as u64) as u32, height)
+            }
+        }
+        CropTarget::Size(w, h) => {
+            if w <= width && h <= height {
+                (w, h)
+            } else {
+                let scale = f64::min(width as f64 / w as f64, height as f64 / h as f64);
+                ((w as f64 * scale).floor() as u32, (h as f64 * scale).floor() as u32)
+            }
+        }
+    };
+    // Even sizes keep the crop valid for yuv420p and most encoders
+    let crop_width = even(crop_width);
+    let crop_height = even(crop_height);
+
+    // Position the crop area according to the anchor unless offsets are given
+    let free_x = width as i32 - crop_width as i32;
+    let free_y = height as i32 - crop_height as i32;
+    let (anchor_x, anchor_y) = match anchor {
+        Anchor::Center => (free_x / 2, free_y / 2),
+        Anchor::Top => (free_x / 2, 0),
+        Anchor::Bottom => (free_x / 2, free_y),
+        Anchor::Left => (0, free_y / 2),
+        Anchor::Right => (free_x, free_y / 2),
+        Anchor::TopLeft => (0, 0),
+        Anchor::TopRight => (free_x, 0),
+        Anchor::BottomLeft => (0, free_y),
+        Anchor::BottomRight => (free_x, free_y),
+    };
+    let x = x_offset.unwrap_or(anchor_x);
+    let y = y_offset.unwrap_or(anchor_y);
+
+    // Ensure x and y are within the video dimensions and aligned to the chroma grid
+    let x = max(0, min(x, free_x)) & !1;
+    let y = max(0, min(y, free_y)) & !1;
+
+    (crop_width, crop_height, x, y)
+}
+
+pub fn parse_aspect(value: &str) -> Result<(u32, u32), String> {
+    // Aspect ratios are written as W:H, e.g. "9:16"
+    let (width, height) = value.split_once(':').ok_or_else(|| format!("Invalid aspect ratio '{}', expected W:H", value))?;
+    let width = width.trim().parse::<u32>().map_err(|e| format!("Invalid aspect ratio '{}': {}", value, e))?;
+    let height = height.trim().parse::<u32>().map_err(|e| format!("Invalid aspect ratio '{}': {}", value, e))?;
+    if width == 0 || height == 0 {
+        return Err(format!("Aspect ratio must not contain zero: {}", value));
+    }
+    Ok((width, height))
+}
+
+pub fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    // Sizes are written as WxH, e.g. "1080x1920"
+    let (width, height) = value.to_lowercase().split_once('x')
+        .map(|(w, h)| (w.trim().to_string(), h.trim().to_string()))
+        .ok_or_else(|| format!("Invalid size '{}', expected WxH", value))?;
+    let width = width.parse::<u32>().map_err(|e| format!("Invalid size '{}': {}", value, e))?;
+    let height = height.parse::<u32>().map_err(|e| format!("Invalid size '{}': {}", value, e))?;
+    if width == 0 || height == 0 {
+        return Err(format!("Size must not contain zero: {}", value));
+    }
+    Ok((width, height))
+}
+
+pub fn get_video_dimensions(input: &str) -> io::Result<(u32, u32)> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0", "-show_entries", "stream=width,height:stream_side_data=rotation:stream_tags=rotate", "-of", "default=noprint_wrappers=1", input])
+        .output()?;
+
+    if output.status.success() {
+        let mut width = 0;
+        let mut height = 0;
+        let mut rotation = 0;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            match line.trim().split_once('=') {
+                Some(("width", value)) => width = value.parse::<u32>().unwrap_or(0),
+                Some(("height", value)) => height = value.parse::<u32>().unwrap_or(0),
+                // Rotation comes from the display matrix side data, or the rotate tag on older files
+                Some(("rotation", value)) | Some(("TAG:rotate", value)) => {
+                    rotation = value.trim().parse::<f64>().map(|r| r.round() as i32).unwrap_or(rotation);
+                }
+                _ => {}
+            }
+        }
+
+        // FFmpeg rotates frames on decode, so filters see the display orientation
+        if rotation.rem_euclid(180) == 90 {
+            Ok((height, width))
+        } else {
+            Ok((width, height))
+        }
+    } else {
+        let error_message = String::from_utf8_lossy(&output.stderr);
+        Err(io::Error::other(format!("FFprobe command failed: {}", error_message)))
+    }
+}
+
+pub fn concatenate_videos(inputs: &[String], output: &str) -> io::Result<()> {
+    // Check if input files exist
+    for input in inputs {
+        if !Path::new(input).exists() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+        }
+    }
+
+    // Create a temporary file list
+    let temp_file = "temp_file_list.txt";
+    {
+        let mut file = File::create(temp_file)?;
+        for input in inputs {
+            writeln!(file, "file '{}'", input)?;
+        }
+    }
+
+    // Construct the FFmpeg command
+    let mut command = Command::new("ffmpeg");
+    command.arg("-f")
+           .arg("concat")
+           .arg("-safe")
+           .arg("0")
+           .arg("-i")
+           .arg(temp_file)
+           .arg("-c")
+           .arg("copy")
+           .arg("-y") // Overwrite output file if it exists
+           .arg(output);
+
+    // Print the command being run
+    println!("Running FFmpeg command: {:?}", command);
+
+    // Run the command with piped stdout and stderr
+    let mut child = command.stdout(Stdio::piped())
+                           .stderr(Stdio::piped())
+                           .spawn()?;
+
+    // Read stdout and stderr
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    if let Some(ref mut stdout_handle) = child.stdout {
+        stdout_handle.read_to_string(&mut stdout)?;
+    }
+    if let Some(ref mut stderr_handle) = child.stderr {
+        stderr_handle.read_to_string(&mut stderr)?;
+    }
+
+    // Wait for the command to finish and check the status
+    let status = child.wait()?;
+
+    // Print stdout and stderr
+    println!("FFmpeg stdout:\n{}", stdout);
+    println!("FFmpeg stderr:\n{}", stderr);
+
+    // Remove the temporary file
+    std::fs::remove_file(temp_file)?;
+
+    if status.success() {
+        println!("Videos concatenated successfully!");
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("FFmpeg command failed with status: {}", status)))
+    }
+}
+
+pub fn frames_to_video(pattern: &str, output: &str, fps: f64, start_number: Option<u32>, crf: u32) -> io::Result<()> {
+    if fps <= 0.0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Frame rate must be greater than zero"));
+    }
+
+    // Either a numbered sequence such as frame_%05d.png or a glob such as frames/*.png
+    let is_glob = pattern.contains(['*', '?', '[']);
+    if !is_glob && !pattern.contains('%') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is neither a numbered pattern (e.g. frame_%05d.png) nor a glob (e.g. 'frames/*.png')", pattern),
+        ));
+    }
+    let folder = Path::new(pattern).parent().filter(|folder| !folder.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    if !folder.is_dir() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Image folder not found: {}", folder.display())));
+    }
+
+    let mut command = Command::new("ffmpeg");
+    command.arg("-framerate").arg(fps.to_string());
+    if is_glob {
+        command.arg("-pattern_type").arg("glob");
+    } else {
+        // The image demuxer only tries the numbers 0 to 4 for the first image,
+        // so look up where sequences such as frame_1001.png start
+        let start_number = match start_number {
+            Some(start_number) => start_number,
+            None => {
+                let file_pattern = file_name_of(Path::new(pattern));
+                let mut names = Vec::new();
+                for entry in std::fs::read_dir(folder)? {
+                    names.push(file_name_of(&entry?.path()));
+                }
+                lowest_frame_number(&file_pattern, names.iter().map(String::as_str)).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, format!("No images matching {} found", pattern))
+                })?
+            }
+        };
+        command.arg("-start_number").arg(start_number.to_string());
+    }
+
+    // H.264 in yuv420p needs even dimensions
+    command.arg("-i")
+           .arg(pattern)
+           .arg("-vf")
+           .arg("scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p")
+           .arg("-c:v")
+           .arg("libx264")
+           .arg("-crf")
+           .arg(crf.to_string())
+           .arg("-y") // Overwrite output file if it exists
+           .arg(output);
+
+    run_ffmpeg(&mut command)?;
+
+    let duration = get_video_duration(output)?;
+    println!("Video created successfully from the image sequence ({} at {} fps)!", format_timestamp(duration), fps);
+    Ok(())
+}
+
+fn lowest_frame_number<'a>(file_pattern: &str, names: impl IntoIterator<Item = &'a str>) -> Option<u32> {
+    // Split a pattern such as frame_%05d.png around its number placeholder
+    let (prefix, rest) = file_pattern.split_once('%')?;
+    let (width, suffix) = rest.split_once('d')?;
+    let width = if width.is_empty() { 0 } else { width.parse::<usize>().ok()? };
+
+    // A name only belongs to the sequence if the pattern expands to it exactly
+    names.into_iter()
+        .filter_map(|name| {
+            let digits = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            let number = digits.parse::<u32>().ok().filter(|_| digits.bytes().all(|b| b.is_ascii_digit()))?;
+            (format!("{:0width$}", number, width = width) == digits).then_some(number)
+        })
+        .min()
+}
+
+pub fn video_to_frames(input: &str, output_dir: &str, every: Option<u32>, fps: Option<f64>, format: FrameFormat, quality: u32, prefix: &str) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+
+    let output_path = Path::new(output_dir);
+    if !output_path.exists() {
+        std::fs::create_dir_all(output_path)?;
+    }
+
+    let extension = format.extension();
+    let output_pattern = output_path.join(format!("{}_%06d.{}", prefix, extension));
+
+    // Have ffmpeg report how many frames it wrote, so images from earlier exports are not counted
+    let temp_dir = tempfile::tempdir()?;
+    let progress_path = temp_dir.path().join("progress.txt");
+    let mut command = Command::new("ffmpeg");
+    command.arg("-progress").arg(&progress_path).arg("-i").arg(input);
+
+    // Pick the frames to keep; without a filter every decoded frame is written once
+    match (every, fps) {
+        (Some(every), _) => {
+            command.arg("-vf")
+                   .arg(format!("select='not(mod(n,{}))'", every))
+                   .arg("-fps_mode")
+                   .arg("vfr");
+        }
+        (None, Some(fps)) => {
+            if fps <= 0.0 {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "Frame rate must be greater than zero"));
+            }
+            command.arg("-vf").arg(format!("fps={}", fps));
+        }
+        (None, None) => {
+            command.arg("-fps_mode").arg("passthrough");
+        }
+    }
+
+    command.args(image_quality_args(format, quality))
+           .arg("-y") // Overwrite output files if they exist
+           .arg(&output_pattern);
+
+    run_ffmpeg(&mut command)?;
+
+    let count = progress_frames(&std::fs::read_to_string(&progress_path)?).unwrap_or(0);
+    println!("Exported {} frame(s) to {}!", count, output_path.display());
+    Ok(())
+}
+
+fn progress_frames(progress: &str) -> Option<u64> {
+    // The progress report repeats a block of key=value lines; the last frame= line is the final count
+    progress.lines()
+        .filter_map(|line| line.trim().strip_prefix("frame="))
+        .next_back()
+        .and_then(|frames| frames.trim().parse().ok())
+}
+
+pub fn parse_timecode(value: &str) -> Result<f64, String> {
+    // Timecodes are seconds ("90.5") or colon separated ("1:30.5", "00:01:30.500")
+    let fields: Vec<&str> = value.trim().split(':').collect();
+    if fields.len() > 3 {
+        return Err(format!("Invalid timecode '{}', expected at most HH:MM:SS", value));
+    }
+
+    let mut seconds = 0.0;
+    for (index, field) in fields.iter().enumerate() {
+        let number = field.parse::<f64>().map_err(|e| format!("Invalid timecode '{}': {}", value, e))?;
+        // Minutes and seconds after the leading field have to stay below 60
+        if !number.is_finite() || number < 0.0 || (index > 0 && number >= 60.0) {
+            return Err(format!("Invalid timecode '{}'", value));
+        }
+        seconds = seconds * 60.0 + number;
+    }
+    Ok(seconds)
+}
+
+pub fn extract_thumbnails(input: &str, output: &str, times: &[f64], count: Option<u32>, width: Option<u32>, height: Option<u32>, quality: u32) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+
+    let output_path = Path::new(output);
+    let format = image_format_of(output_path)?;
+    if let Some(parent) = output_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    // Keep the aspect ratio when only one side is given
+    let scale = match (width, height) {
+        (None, None) => None,
+        (width, height) => Some(format!(
+            "scale={}:{}",
+            width.map_or("-2".to_string(), |w| even(w).to_string()),
+            height.map_or("-2".to_string(), |h| even(h).to_string())
+        )),
+    };
+
+    let duration = get_video_duration(input)?;
+    let times: Vec<f64> = match count {
+        // Evenly spaced, leaving out the very first and last frames which are often black
+        Some(count) => (1..=count.max(1)).map(|i| duration * i as f64 / (count.max(1) + 1) as f64).collect(),
+        None => times.to_vec(),
+    };
+    if let Some(time) = times.iter().find(|&&time| time >= duration) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Timecode {} is past the end of the video ({})", format_timestamp(*time), format_timestamp(duration)),
+        ));
+    }
+
+    if times.is_empty() {
+        // Sample frames evenly across the whole clip and let the thumbnail filter
+        // pick the most representative one of those samples
+        const SAMPLES: u32 = 100;
+        if duration <= 0.0 {
+            return Err(io::Error::other(format!("Could not determine the duration of {}", input)));
+        }
+        let mut filter = format!("fps={:.6},thumbnail={}", SAMPLES as f64 / duration, SAMPLES);
+        if let Some(scale) = &scale {
+            filter.push_str(&format!(",{}", scale));
+        }
+
+        let mut command = Command::new("ffmpeg");
+        command.arg("-i")
+               .arg(input)
+               .arg("-vf")
+               .arg(&filter)
+               .arg("-frames:v")
+               .arg("1")
+               .args(image_quality_args(format, quality))
+               .arg("-y") // Overwrite output file if it exists
+               .arg(output_path);
+
+        run_ffmpeg(&mut command)?;
+
+        println!("Thumbnail saved to {}!", output_path.display());
+        return Ok(());
+    }
+
+    // Number the files when there is more than one still
+    let paths: Vec<PathBuf> = if times.len() == 1 {
+        vec![output_path.to_path_buf()]
+    } else {
+        let stem = output_path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        (1..=times.len())
+            .map(|index| output_path.with_file_name(format!("{}_{:02}.{}", stem, index, format.extension())))
+            .collect()
+    };
+
+    for (time, path) in times.iter().zip(&paths) {
+        // Seeking before the input is fast and still lands on the exact frame
+        let mut command = Command::new("ffmpeg");
+        command.arg("-ss")
+               .arg(format!("{:.3}", time))
+               .arg("-i")
+               .arg(input);
+        if let Some(scale) = &scale {
+            command.arg("-vf").arg(scale);
+        }
+        command.arg("-frames:v")
+               .arg("1")
+               .args(image_quality_args(format, quality))
+               .arg("-y") // Overwrite output file if it exists
+               .arg(path);
+
+        run_ffmpeg(&mut command)?;
+    }
+
+    println!("Extracted {} thumbnail(s)!", paths.len());
+    for (time, path) in times.iter().zip(&paths) {
+        println!("  {} ({})", path.display(), format_timestamp(*time));
+    }
+    Ok(())
+}
+
+pub fn contact_sheet(input: &str, output: &str, columns: u32, rows: u32, tile_width: u32, font: Option<&str>) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+    if columns == 0 || rows == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "The grid needs at least one column and one row"));
+    }
+
+    let output_path = Path::new(output);
+    let format = image_format_of(output_path)?;
+    let duration = get_video_duration(input)?;
+    let (width, height) = get_video_dimensions(input)?;
+    let (video_codec, audio_codec) = get_codecs(input)?;
+
+    let tile_width = even(tile_width);
+    let font_size = max(12, tile_width / 16);
+    let fontfile = font.map(|font| format!(":fontfile={}", filter_quote(font))).unwrap_or_default();
+
+    // Grab one labelled frame from the middle of each equal part of the video.
+    let temp_dir = tempfile::tempdir()?;
+    let count = columns * rows;
+    for index in 0..count {
+        let time = duration * (index as f64 + 0.5) / count as f64;
+        // The label is read from a file, as the colons of the timestamp would split the filter options
+        let label_path = temp_dir.path().join(format!("label_{:04}.txt", index));
+        std::fs::write(&label_path, format_timestamp(time))?;
+        let filter = format!(
+            "scale={}:-2,drawtext=textfile={}:expansion=none:fontsize={}:fontcolor=white:box=1:boxcolor=black@0.6:boxborderw=4:x=w-text_w-8:y=h-text_h-8{}",
+            tile_width, filter_quote(&label_path.display().to_string()), font_size, fontfile
+        );
+        let output = Command::new("ffmpeg")
+            .arg("-ss")
+            .arg(format!("{:.3}", time))
+            .arg("-i")
+            .arg(input)
+            .arg("-vf")
+            .arg(&filter)
+            .args(["-frames:v", "1", "-y"])
+            .arg(temp_dir.path().join(format!("tile_{:04}.png", index)))
+            .output()?;
+
+        if !output.status.success() {
+            let error_message = String::from_utf8_lossy(&output.stderr);
+            return Err(io::Error::other(format!("FFmpeg command failed at {}: {}", format_timestamp(time), error_message)));
+        }
+    }
+
+    // Header with what the probe knows about the file
+    let file_name = Path::new(input).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_else(|| input.to_string());
+    let header = format!(
+        "{}\nDuration: {}   Resolution: {}x{}   Video: {}   Audio: {}",
+        file_name,
+        format_timestamp(duration),
+        width,
+        height,
+        video_codec.as_deref().unwrap_or("none"),
+        audio_codec.as_deref().unwrap_or("none")
+    );
+    let header_path = temp_dir.path().join("header.txt");
+    std::fs::write(&header_path, header)?;
+
+    let header_font_size = font_size * 3 / 2;
+    let header_height = header_font_size * 4;
+    let filter = format!(
+        "tile={}x{}:margin=8:padding=8:color=0x202020,pad=iw:ih+{}:0:{}:color=0x202020,\
+         drawtext=textfile={}:expansion=none:fontsize={}:fontcolor=white:line_spacing={}:x=16:y=16{}",
+        columns, rows, header_height, header_height,
+        filter_quote(&header_path.display().to_string()), header_font_size, header_font_size / 2, fontfile
+    );
+
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i")
+           .arg(temp_dir.path().join("tile_%04d.png"))
+           .arg("-vf")
+           .arg(&filter)
+           .arg("-frames:v")
+           .arg("1")
+           .args(image_quality_args(format, 90))
+           .arg("-y") // Overwrite output file if it exists
+           .arg(output_path);
+
+    run_ffmpeg(&mut command)?;
+
+    println!("Contact sheet with {} frames saved to {}!", count, output_path.display());
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn sprite_sheets(input: &str, output_dir: &str, interval: f64, tile_width: u32, columns: u32, rows: u32, format: FrameFormat, prefix: &str) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+    if interval <= 0.0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Interval must be greater than zero"));
+    }
+    if columns == 0 || rows == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "The grid needs at least one column and one row"));
+    }
+
+    let output_path = Path::new(output_dir);
+    if !output_path.exists() {
+        std::fs::create_dir_all(output_path)?;
+    }
+
+    // Every thumbnail gets the same size so the track can address it by position
+    let duration = get_video_duration(input)?;
+    let (width, height) = get_video_dimensions(input)?;
+    let tile_width = even(tile_width);
+    let tile_height = even((tile_width as f64 * height as f64 / width.max(1) as f64).round() as u32);
+
+    let extension = format.extension();
+    let output_pattern = output_path.join(format!("{}_%03d.{}", prefix, extension));
+    let filter = format!(
+        "fps=1/{},scale={}:{},setsar=1,tile={}x{}",
+        interval, tile_width, tile_height, columns, rows
+    );
+
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i")
+           .arg(input)
+           .arg("-vf")
+           .arg(&filter)
+           .arg("-an")
+           .args(image_quality_args(format, 80))
+           .arg("-y") // Overwrite output files if they exist
+           .arg(&output_pattern);
+
+    run_ffmpeg(&mut command)?;
+
+    let track = sprite_track(duration, interval, columns, rows, tile_width, tile_height, prefix, extension);
+    let track_path = output_path.join(format!("{}.vtt", prefix));
+    std::fs::write(&track_path, track)?;
+
+    let thumbnails = (duration / interval).ceil().max(1.0) as u32;
+    let per_sheet = columns * rows;
+    println!(
+        "Created {} sprite sheet(s) with {} thumbnails of {}x{} and the track {}!",
+        thumbnails.div_ceil(per_sheet), thumbnails, tile_width, tile_height, track_path.display()
+    );
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn sprite_track(duration: f64, interval: f64, columns: u32, rows: u32, tile_width: u32, tile_height: u32, prefix: &str, extension: &str) -> String {
+    // Map each interval to its thumbnail; the image muxer numbers the sheets from 1
+    let thumbnails = (duration / interval).ceil().max(1.0) as u32;
+    let per_sheet = columns * rows;
+    let mut track = String::from("WEBVTT\n");
+    for index in 0..thumbnails {
+        let start = index as f64 * interval;
+        let end = (start + interval).min(duration);
+        let position = index % per_sheet;
+        track.push_str(&format!(
+            "\n{} --> {}\n{}_{:03}.{}#xywh={},{},{},{}\n",
+            format_timestamp(start),
+            format_timestamp(end),
+            prefix,
+            index / per_sheet + 1,
+            extension,
+            (position % columns) * tile_width,
+            (position / columns) * tile_height,
+            tile_width,
+            tile_height
+        ));
+    }
+    track
+}
+
+#[derive(Clone, Debug)]
+pub struct AnimationOptions {
+    /// Frames per second of the animation
+    pub fps: f64,
+    /// Width in pixels; the height follows the aspect ratio
+    pub width: Option<u32>,
+    /// Dithering used when mapping GIF frames to the palette
+    pub dither: Dither,
+    /// Maximum number of GIF palette colors
+    pub colors: u32,
+    /// How many times the animation plays, 0 for forever
+    pub plays: u32,
+    /// Start of the range to export, in seconds
+    pub start: Option<f64>,
+    /// End of the range to export, in seconds
+    pub end: Option<f64>,
+    /// WebP quality from 1 to 100
+    pub quality: u32,
+}
+
+pub fn create_animation(input: &str, output: &str, options: &AnimationOptions) -> io::Result<()> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("Input file not found: {}", input)));
+    }
+    if options.fps <= 0.0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Frame rate must be greater than zero"));
+    }
+
+    // Only decode the requested range
+    let mut range_args: Vec<String> = Vec::new();
+    let start = options.start.unwrap_or(0.0);
+    if start > 0.0 {
+        range_args.extend(["-ss".to_string(), format!("{:.3}", start)]);
+    }
+    if let Some(end) = options.end {
+        if end <= start {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "End time must be after the start time"));
+        }
+        range_args.extend(["-t".to_string(), format!("{:.3}", end - start)]);
+    }
+
+    let scale = match options.width {
+        Some(width) => format!(",scale={}:-2:flags=lanczos", width),
+        None => String::new(),
+    };
+    let frames_filter = format!("fps={}{}", options.fps, scale);
+
+    let extension = container_extension(output);
+    let mut command = Command::new("ffmpeg");
+    command.args(&range_args).arg("-i").arg(input);
+
+    let temp_dir = tempfile::tempdir()?;
+    match extension.as_str() {
+        "gif" => {
+            // First pass: build a palette tuned to the frames, favouring the parts that move
+            let palette_path = temp_dir.path().join("palette.png");
+            let mut palette_command = Command::new("ffmpeg");
+            palette_command.args(&range_args)
+                           .arg("-i")
+                           .arg(input)
+                           .arg("-vf")
+                           .arg(format!("{},palettegen=max_colors={}:stats_mode=diff", frames_filter, options.colors.clamp(2, 256)))
+                           .arg("-y")
+                           .arg(&palette_path);
+
+            run_ffmpeg(&mut palette_command)?;
+
+            // Second pass: map the frames to the palette, only redrawing what changed.
+            // GIF counts repeats rather than plays, and -1 means play once.
+            let repeats = match options.plays {
+                0 => 0,
+                1 => -1,
+                plays => plays as i64 - 1,
+            };
+            command.arg("-i")
+                   .arg(&palette_path)
+                   .arg("-lavfi")
+                   .arg(format!("[0:v]{}[x];[x][1:v]paletteuse=dither={}:diff_mode=rectangle", frames_filter, options.dither.filter_name()))
+                   .arg("-loop")
+                   .arg(repeats.to_string());
+        }
+        "webp" => {
+            command.arg("-vf")
+                   .arg(&frames_filter)
+                   .args(["-c:v", "libwebp", "-lossless", "0", "-quality"])
+                   .arg(options.quality.clamp(1, 100).to_string())
+                   .arg("-loop")
+                   .arg(options.plays.to_string());
+        }
+        "apng" | "png" => {
+            command.arg("-vf")
+                   .arg(format!("{},format=rgba", frames_filter))
+                   .args(["-f", "apng", "-plays"])
+                   .arg(options.plays.to_string());
+        }
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Unsupported animation format for {}, use .gif, .webp or .apng", output),
+            ));
+        }
+    }
+
+    command.arg("-an")
+           .arg("-y") // Overwrite output file if it exists
+           .arg(output);
+
+    run_ffmpeg(&mut command)?;
+
+    let size = std::fs::metadata(output).map(|metadata| metadata.len()).unwrap_or(0);
+    println!("Animation saved to {} ({:.1} KB)!", output, size as f64 / 1024.0);
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum StreamCodec {
+    /// Let FFmpeg pick the encoder for the container
+    #[default]
+    Default,
+    /// Leave the stream out of the output
+    Drop,
+    /// A named encoder, e.g. "libx264"
+    Encoder(String),
+}
+
+impl StreamCodec {
+    fn parse(value: &str) -> StreamCodec {
+        match value {
+            "" => StreamCodec::Default,
+            "none" => StreamCodec::Drop,
+            encoder => StreamCodec::Encoder(encoder.to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Preset {
+    pub name: String,
+    pub description: String,
+    pub video_codec: StreamCodec,
+    /// Constant rate factor for quality-based rate control
+    pub crf: Option<u32>,
+    /// Video bitrate, e.g. "5M" ("0" lets some encoders use the CRF alone)
+    pub video_bitrate: Option<String>,
+    /// Encoder speed preset, e.g. "slow"
+    pub speed: Option<String>,
+    /// Encoder profile, e.g. "high" or "dnxhr_sq"
+    pub profile: Option<String>,
+    pub pix_fmt: Option<String>,
+    pub audio_codec: StreamCodec,
+    /// Audio bitrate, e.g. "128k"
+    pub audio_bitrate: Option<String>,
+    /// Extension of the output container
+    pub container: String,
+    /// Any other FFmpeg output options
+    pub extra_args: Vec<String>,
+}
+
+impl Preset {
+    fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let StreamCodec::Encoder(codec) = &self.video_codec {
+            args.extend(["-c:v".to_string(), codec.clone()]);
+        }
+        match &self.video_codec {
+            StreamCodec::Drop => args.push("-vn".to_string()),
+            _ => {
+                let options = [
+                    ("-crf", self.crf.map(|crf| crf.to_string())),
+                    ("-b:v", self.video_bitrate.clone()),
+                    ("-preset", self.speed.clone()),
+                    ("-profile:v", self.profile.clone()),
+                    ("-pix_fmt", self.pix_fmt.clone()),
+                ];
+                for (flag, value) in options {
+                    if let Some(value) = value {
+                        args.extend([flag.to_string(), value]);
+                    }
+                }
+            }
+        }
+        if let StreamCodec::Encoder(codec) = &self.audio_codec {
+            args.extend(["-c:a".to_string(), codec.clone()]);
+        }
+        match &self.audio_codec {
+            StreamCodec::Drop => args.push("-an".to_string()),
+            _ => {
+                if let Some(bitrate) = &self.audio_bitrate {
+                    args.extend(["-b:a".to_string(), bitrate.clone()]);
+                }
+            }
+        }
+        args.extend(self.extra_args.iter().cloned());
+        args
+    }
+}
+
+fn builtin_presets() -> Vec<Preset> {
+    let preset = |name: &str, description: &str, video_codec: Option<&str>, audio_codec: Option<&str>, container: &str| Preset {
+        name: name.to_string(),
+        description: description.to_string(),
+        video_codec: StreamCodec::parse(video_codec.unwrap_or("none")),
+        audio_codec: StreamCodec::parse(audio_codec.unwrap_or("none")),
+        container: container.to_string(),
+        ..Default::default()
+    };
+    let strings = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<_>>();
+
+    vec![
+        Preset {
+            crf: Some(23),
+            speed: Some("medium".to_string()),
+            profile: Some("high".to_string()),
+            pix_fmt: Some("yuv420p".to_string()),
+            audio_bitrate: Some("128k".to_string()),
+            extra_args: strings(&["-movflags", "+faststart"]),
+            ..preset("web-h264", "H.264/AAC MP4 that plays everywhere, streamable", Some("libx264"), Some("aac"), "mp4")
+        },
+        Preset {
+            crf: Some(32),
+            video_bitrate: Some("0".to_string()),
+            pix_fmt: Some("yuv420p".to_string()),
+            audio_bitrate: Some("128k".to_string()),
+            extra_args: strings(&["-row-mt", "1"]),
+            ..preset("web-vp9", "VP9/Opus WebM for browsers", Some("libvpx-vp9"), Some("libopus"), "webm")
+        },
+        Preset {
+            crf: Some(35),
+            speed: Some("8".to_string()),
+            pix_fmt: Some("yuv420p10le".to_string()),
+            audio_bitrate: Some("128k".to_string()),
+            extra_args: strings(&["-movflags", "+faststart"]),
+            ..preset("av1", "AV1/Opus MP4, small files for modern players", Some("libsvtav1"), Some("libopus"), "mp4")
+        },
+        Preset {
+            crf: Some(20),
+            speed: Some("slow".to_string()),
+            pix_fmt: Some("yuv420p10le".to_string()),
+            ..preset("hevc-archive", "10-bit HEVC with lossless FLAC audio in MKV for archiving", Some("libx265"), Some("flac"), "mkv")
+        },
+        Preset {
+            profile: Some("0".to_string()),
+            pix_fmt: Some("yuv422p10le".to_string()),
+            ..preset("prores-proxy", "ProRes 422 Proxy MOV for editing", Some("prores_ks"), Some("pcm_s16le"), "mov")
+        },
+        Preset {
+            profile: Some("dnxhr_sq".to_string()),
+            pix_fmt: Some("yuv422p".to_string()),
+            ..preset("dnxhr", "DNxHR SQ MOV for editing", Some("dnxhd"), Some("pcm_s16le"), "mov")
+        },
+        Preset {
+            audio_bitrate: Some("192k".to_string()),
+            ..preset("mp3", "Audio only, MP3", None, Some("libmp3lame"), "mp3")
+        },
+        Preset {
+            audio_bitrate: Some("128k".to_string()),
+            ..preset("opus", "Audio only, Opus", None, Some("libopus"), "opus")
+        },
+    ]
+}
+
+fn default_presets_path() -> Option<PathBuf> {
+    // $XDG_CONFIG_HOME/ffmpeg-cli/presets.ini, falling back to ~/.config
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|config| config.join("ffmpeg-cli").join("presets.ini"))
+}
+
+fn load_presets(config: Option<&str>) -> Result<Vec<Preset>, Box<dyn std::error::Error>> {
+    let mut presets = builtin_presets();
+
+    // An explicit config file has to exist; the default one is optional
+    let path = match config {
+        Some(config) => Some(PathBuf::from(config)),
+        None => default_presets_path().filter(|path| path.is_file()),
+    };
+    if let Some(path) = path {
+        for preset in read_presets_file(&path, &presets)? {
+            // User presets replace built-in presets of the same name
+            presets.retain(|existing| existing.name != preset.name);
+            presets.push(preset);
+        }
+    }
+    Ok(presets)
+}
+
+fn read_presets_file(path: &Path, builtin: &[Preset]) -> Result<Vec<Preset>, Box<dyn std::error::Error>> {
+    // An INI file with one [section] per preset and "key = value" lines.
+    // Blank lines and lines starting with '#' or ';' are skipped.
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Could not read presets {}: {}", path.display(), e))?;
+
+    let mut presets: Vec<Preset> = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(format!("{}:{}: empty preset name", path.display(), number + 1).into());
+            }
+            presets.push(Preset { name: name.to_string(), container: "mp4".to_string(), ..Default::default() });
+            continue;
+        }
+
+        let (key, value) = line.split_once('=')
+            .ok_or_else(|| format!("{}:{}: expected 'key = value'", path.display(), number + 1))?;
+        let (key, value) = (key.trim(), value.trim());
+        if presets.is_empty() {
+            return Err(format!("{}:{}: '{}' appears before any [preset] section", path.display(), number + 1, key).into());
+        }
+
+        // Start from an earlier preset of this file or a built-in one, keeping the name of this one
+        if key == "base" {
+            let base = presets.iter()
+                .rev()
+                .skip(1)
+                .chain(builtin)
+                .find(|base| base.name == value)
+                .cloned()
+                .ok_or_else(|| format!("{}:{}: unknown base preset '{}'", path.display(), number + 1, value))?;
+            let preset = presets.last_mut().unwrap();
+            *preset = Preset { name: preset.name.clone(), description: format!("Based on {}", base.name), ..base };
+            continue;
+        }
+
+        let preset = presets.last_mut().unwrap();
+        // Other settings are cleared by an empty value or "none"; for codecs, "none" drops the stream
+        // and an empty value leaves the choice of encoder to FFmpeg
+        let optional = |value: &str| (!value.is_empty() && value != "none").then(|| value.to_string());
+
+        match key {
+            "description" => preset.description = value.to_string(),
+            "video_codec" => preset.video_codec = StreamCodec::parse(value),
+            "crf" => {
+                preset.crf = match optional(value) {
+                    Some(crf) => Some(crf.parse::<u32>().map_err(|e| format!("{}:{}: invalid crf '{}': {}", path.display(), number + 1, crf, e))?),
+                    None => None,
+                };
+            }
+            "video_bitrate" => preset.video_bitrate = optional(value),
+            "speed" => preset.speed = optional(value),
+            "profile" => preset.profile = optional(value),
+            "pix_fmt" => preset.pix_fmt = optional(value),
+            "audio_codec" => preset.audio_codec = StreamCodec::parse(value),
+            "audio_bitrate" => preset.audio_bitrate = optional(value),
+            "container" => preset.container = value.trim_start_matches('.').to_string(),
+            "args" => preset.extra_args = value.split_whitespace().map(str::to_string).collect(),
+            _ => return Err(format!("{}:{}: unknown key '{}'", path.display(), number + 1, key).into()),
+        }
+    }
+
+    Ok(presets)
+}
+
+pub fn list_presets(config: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    for preset in load_presets(config)? {
+        println!("{:<16} .{:<5} {}", preset.name, preset.container, preset.description);
+    }
+    Ok(())
+}
+
+pub fn parse_file_size(value: &str) -> Result<u64, String> {
+    // Sizes are bytes with an optional unit: "8MB", "50M", "1.5GB", "700MiB"
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number = number.parse::<f64>().map_err(|e| format!("Invalid size '{}': {}", value, e))?;
+    let multiplier = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "k" | "kb" => 1e3,
+        "m" | "mb" => 1e6,
+        "g" | "gb" => 1e9,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        unit => return Err(format!("Invalid size unit '{}' in '{}'", unit, value)),
+    };
+    let bytes = (number * multiplier).round() as u64;
+    if bytes == 0 {
+        return Err(format!("Size must be greater than zero: {}", value));
+    }
+    Ok(bytes)
+}
+
+pub fn transcode(input: &str, output: &str, preset_name: &str, config: Option<&str>, target_size: Option<u64>, audio_bitrate: Option<u32>) -> Result<(), Box<dyn std::error::Error>> {
+    // Check if input file exists
+    if !Path::new(input).exists() {
+        return Err(format!("Input file not found: {}", input).into());
+    }
+
+    let presets = load_presets(config)?;
+    let mut preset = presets.iter()
+        .find(|preset| preset.name == preset_name)
+        .cloned()
+        .ok_or_else(|| {
+            let names: Vec<&str> = presets.iter().map(|preset| preset.name.as_str()).collect();
+            format!("Unknown preset '{}', available presets: {}", preset_name, names.join(", "))
+        })?;
+    if let Some(audio_bitrate) = audio_bitrate {
+        preset.audio_bitrate = Some(format!("{}k", audio_bitrate));
+    }
+
+    // Use the container of the preset when the output has no extension
+    let mut output_path = PathBuf::from(output);
+    match output_path.extension().and_then(|e| e.to_str()) {
+        None => {
+            output_path.set_extension(&preset.container);
+        }
+        Some(extension) if !extension.eq_ignore_ascii_case(&preset.container) => {
+            eprintln!("Warning: preset {} is meant for .{} files, writing .{}", preset.name, preset.container, extension);
+        }
+        Some(_) => {}
+    }
+
+    if let Some(target_size) = target_size {
+        return encode_to_size(input, &output_path, &preset, target_size);
+    }
+
+    let mut command = Command::new("ffmpeg");
+    command.arg("-i")
+           .arg(input)
+           .args(preset.args())
+           .arg("-y") // Overwrite output file if it exists
+           .arg(&output_path);
+
+    run_ffmpeg(&mut command)?;
+
+    println!("Transcoded successfully with preset {} to {}!", preset.name, output_path.display());
+    Ok(())
+}
+
+fn encode_to_size(input: &str, output_path: &Path, preset: &Preset, target_size: u64) -> Result<(), Box<dyn std::error::Error>> {
+    // Spread the size over the duration, keeping a little room for the container overhead
+    const OVERHEAD: f64 = 0.97;
+    let duration = get_video_duration(input)?;
+    if duration <= 0.0 {
+        return Err(format!("Could not determine the duration of {}", input).into());
+    }
+    let (has_video, has_audio) = get_stream_info(input)?;
+    let total_kbps = target_size as f64 * 8.0 / duration / 1000.0 * OVERHEAD;
+    let mut audio_kbps = match (&preset.audio_codec, has_audio) {
+        (StreamCodec::Drop, _) | (_, false) => 0.0,
+        _ => match preset.audio_bitrate.as_deref() {
+            Some(bitrate) => parse_kbps(bitrate)
+                .ok_or_else(|| format!("Preset {} has an invalid audio_bitrate: {}", preset.name, bitrate))?,
+            None => 128.0,
+        },
+    };
+
+    let mut preset = preset.clone();
+    preset.crf = None;
+    let video_kbps = (total_kbps - audio_kbps).floor();
+
+    let temp_dir = tempfile::tempdir()?;
+    match preset.video_codec.clone() {
+        StreamCodec::Default if has_video => {
+            return Err(format!("Preset {} does not name a video encoder, which two-pass encoding needs", preset.name).into());
+        }
+        StreamCodec::Encoder(codec) if has_video => {
+            if !matches!(codec.as_str(), "libx264" | "libx265" | "libvpx" | "libvpx-vp9" | "libaom-av1") {
+                return Err(format!("Preset {} uses {}, which has no two-pass mode; use a preset like web-h264 or web-vp9", preset.name, codec).into());
+            }
+            if video_kbps < 16.0 {
+                return Err(format!(
+                    "A target of {:.2} MB leaves no room for video over {} at {:.0} kbps audio",
+                    target_size as f64 / 1e6, format_timestamp(duration), audio_kbps
+                ).into());
+            }
+            preset.video_bitrate = Some(format!("{}k", video_kbps));
+            preset.audio_bitrate = (audio_kbps > 0.0).then(|| format!("{}k", audio_kbps));
+
+            // Two passes: the first only analyses the video, the second spends the bits where they are needed
+            let passlog = temp_dir.path().join("passlog");
+            let pass_args = |pass: u32| -> Vec<String> {
+                match codec.as_str() {
+                    "libx265" => vec!["-x265-params".to_string(), format!("pass={}:stats={}", pass, passlog.with_extension("log").display())],
+                    _ => vec!["-pass".to_string(), pass.to_string(), "-passlogfile".to_string(), passlog.display().to_string()],
+                }
+            };
+
+            let analysis = Preset { audio_codec: StreamCodec::Drop, extra_args: Vec::new(), ..preset.clone() };
+            let mut first_pass = Command::new("ffmpeg");
+            first_pass.arg("-i")
+                      .arg(input)
+                      .args(analysis.args())
+                      .args(pass_args(1))
+                      .args(["-f", "null", "-y", "-"]);
+
+            run_ffmpeg(&mut first_pass)?;
+
+            let mut second_pass = Command::new("ffmpeg");
+            second_pass.arg("-i")
+                       .arg(input)
+                       .args(preset.args())
+                       .args(pass_args(2))
+                       .arg("-y") // Overwrite output file if it exists
+                       .arg(output_path);
+
+            run_ffmpeg(&mut second_pass)?;
+        }
+        _ => {
+            // Without video the whole budget goes to the audio, and a single pass is enough
+            preset.video_codec = StreamCodec::Drop;
+            audio_kbps = total_kbps.floor();
+            preset.audio_bitrate = Some(format!("{}k", audio_kbps));
+
+            let mut command = Command::new("ffmpeg");
+            command.arg("-i")
+                   .arg(input)
+                   .args(preset.args())
+                   .arg("-y") // Overwrite output file if it exists
+                   .arg(output_path);
+
+            run_ffmpeg(&mut command)?;
+        }
+    }
+
+    // Rate control is approximate, so check what was actually written
+    let size = std::fs::metadata(output_path)?.len();
+    let summary = format!(
+        "{} is {:.2} MB for a target of {:.2} MB ({:.0} kbps video, {:.0} kbps audio)",
+        output_path.display(), size as f64 / 1e6, target_size as f64 / 1e6,
+        if preset.video_codec == StreamCodec::Drop { 0.0 } else { video_kbps }, audio_kbps
+    );
+    if size > target_size {
+        return Err(format!("{}; try a smaller --target-size or --audio-bitrate", summary).into());
+    }
+
+    println!("Transcoded successfully with preset {}: {}!", preset.name, summary);
+    Ok(())
+}
+
+fn parse_kbps(value: &str) -> Option<f64> {
+    // Bitrates are written as "128k", "1.5M" or plain bits per second
+    let value = value.trim().to_lowercase();
+    let (number, multiplier) = match value.strip_suffix('k') {
+        Some(number) => (number, 1.0),
+        None => match value.strip_suffix('m') {
+            Some(number) => (number, 1000.0),
+            None => (value.as_str(), 0.001),
+        },
+    };
+    number.parse::<f64>()
+        .ok()
+        .map(|number| number * multiplier)
+        .filter(|kbps| kbps.is_finite() && *kbps > 0.0)
+}
+
+fn image_format_of(path: &Path) -> io::Result<FrameFormat> {
+    let extension = path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).unwrap_or_default();
+    match extension.as_str() {
+        "jpg" | "jpeg" => Ok(FrameFormat::Jpg),
+        "png" => Ok(FrameFormat::Png),
+        "webp" => Ok(FrameFormat::Webp),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Unsupported image format for {}, use .jpg, .png or .webp", path.display()),
+        )),
+    }
+}
+
+fn image_quality_args(format: FrameFormat, quality: u32) -> Vec<String> {
+    // Map the 1-100 quality onto each encoder's own scale; PNG and TIFF are lossless
+    let quality = quality.clamp(1, 100);
+    match format {
+        FrameFormat::Jpg => vec!["-q:v".to_string(), (2 + (100 - quality) * 29 / 99).to_string()],
+        FrameFormat::Webp => vec!["-quality".to_string(), quality.to_string()],
+        FrameFormat::Png | FrameFormat::Tiff => Vec::new(),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Slide {
+    pub path: PathBuf,
+    pub duration: f64,
+    pub caption: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SlideshowOptions {
+    /// Default duration of each image in seconds
+    pub duration: f64,
+    /// Order of the images found in the input folder
+    pub sort: SlideOrder,
+    /// Manifest listing images with their own durations and captions
+    pub manifest: Option<String>,
+    /// Size of the output canvas (width, height)
+    pub size: (u32, u32),
+    /// How images are fitted into the canvas
+    pub fit: SlideFit,
+    /// Pan and zoom motion applied to every image
+    pub ken_burns: Option<KenBurns>,
+    /// Transition between images
+    pub transition: Option<Transition>,
+    /// Length of each transition in seconds
+    pub transition_duration: f64,
+    /// Background music, looped or cut to the length of the slideshow
+    pub audio: Option<String>,
+    /// Length of the music fade-in and fade-out in seconds
+    pub audio_fade: f64,
+    /// Scale the image durations so the slideshow is as long as the music
+    pub fit_to_audio: bool,
+    /// Use the file name of an image as its caption when the manifest gives none
+    pub caption_from_filename: bool,
+    /// Font file for captions and title cards (default: FFmpeg's default font)
+    pub font: Option<String>,
+    /// Caption font size in pixels; title cards use twice this size
+    pub font_size: u32,
+    /// Text color of captions and title cards
+    pub font_color: String,
+    /// Draw a box behind captions
+    pub caption_box: bool,
+    /// Color of the caption box
+    pub box_color: String,
+    /// Where captions are placed on the image
+    pub caption_position: CaptionPosition,
+    /// Text of a title card shown before the first image
+    pub intro: Option<String>,
+    /// Text of a title card shown after the last image
+    pub outro: Option<String>,
+    /// Duration of each title card in seconds
+    pub title_duration: f64,
+    /// Title card background: a color, or two comma-separated hex colors for a vertical gradient
+    pub title_background: String,
+}
+
+const SLIDESHOW_FPS: u32 = 30;
+
+pub fn create_slideshow(input_folder: &str, output: &str, options: &SlideshowOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let current_dir = std::env::current_dir()?;
+    let input_path = make_absolute_path(input_folder, &current_dir);
+    let output_path = make_absolute_path(output, &current_dir);
+
+    if !input_path.is_dir() {
+        return Err(format!("{} is not a directory", input_path.display()).into());
+    }
+    if options.duration <= 0.0 {
+        return Err("Image duration must be greater than zero".into());
+    }
+
+    let mut slides = match &options.manifest {
+        Some(manifest) => read_manifest(&make_absolute_path(manifest, &current_dir), &input_path, options.duration)?,
+        None => collect_slides(&input_path, options.sort, options.duration)?,
+    };
+    if slides.is_empty() {
+        return Err(format!("No images found in {}", input_path.display()).into());
+    }
+
+    if options.caption_from_filename {
+        for slide in slides.iter_mut().filter(|slide| slide.caption.is_none()) {
+            slide.caption = slide.path.file_stem().map(|stem| stem.to_string_lossy().replace(['_', '-'], " "));
+        }
+    }
+
+    let audio = options.audio.as_ref().map(|audio| make_absolute_path(audio, &current_dir));
+    if let Some(audio) = &audio {
+        if !audio.is_file() {
+            return Err(format!("Audio file not found: {}", audio.display()).into());
+        }
+    }
+
+    let title_count = options.intro.iter().chain(options.outro.iter()).count();
+    let part_count = slides.len() + title_count;
+    if title_count > 0 && options.title_duration <= 0.0 {
+        return Err("Title card duration must be greater than zero".into());
+    }
+
+    // Transitions overlap neighbouring parts, shortening the slideshow by one transition per cut
+    let overlap = match options.transition {
+        Some(_) if part_count > 1 => options.transition_duration,
+        _ => 0.0,
+    };
+    if options.transition.is_some() && options.transition_duration <= 0.0 {
+        return Err("Transition duration must be greater than zero".into());
+    }
+
+    // Stretch or shrink every image duration so the slideshow ends with the music
+    if let (Some(audio), true) = (&audio, options.fit_to_audio) {
+        let audio_duration = get_video_duration(audio.to_str().unwrap())?;
+        let image_total: f64 = slides.iter().map(|slide| slide.duration).sum();
+        let title_total = slide_duration(options.title_duration) * title_count as f64;
+        let factor = (audio_duration + overlap * (part_count - 1) as f64 - title_total) / image_total;
+        if factor <= 0.0 {
+            return Err("The music is too short to fit the title cards".into());
+        }
+        for slide in &mut slides {
+            slide.duration *= factor;
+        }
+    }
+
+    // Title cards go around the images
+    let mut parts: Vec<(String, f64)> = Vec::new();
+    if options.intro.is_some() {
+        parts.push(("Intro title card".to_string(), slide_duration(options.title_duration)));
+    }
+    for slide in &slides {
+        parts.push((slide.path.display().to_string(), slide_duration(slide.duration)));
+    }
+    if options.outro.is_some() {
+        parts.push(("Outro title card".to_string(), slide_duration(options.title_duration)));
+    }
+
+    // Each part has to outlast the transitions into and out of it
+    for (index, (name, duration)) in parts.iter().enumerate() {
+        let transitions = if index == 0 || index + 1 == parts.len() { 1.0 } else { 2.0 };
+        if overlap > 0.0 && *duration <= overlap * transitions {
+            return Err(format!("{} is shown for {:.2}s, too short for {:.2}s transitions", name, duration, overlap).into());
+        }
+    }
+
+    // Render every part as a clip on the same canvas, so mixed sizes concatenate cleanly.
+    let temp_dir = tempfile::tempdir()?;
+    let mut clips = Vec::new();
+    if let Some(intro) = &options.intro {
+        let clip_path = temp_dir.path().join("intro.mp4");
+        render_title_card(intro, &clip_path, options)?;
+        clips.push(clip_path);
+    }
+    for (index, slide) in slides.iter().enumerate() {
+        let clip_path = temp_dir.path().join(format!("slide_{:04}.mp4", index));
+        let motion = options.ken_burns.map(|direction| match direction {
+            KenBurns::Random => random_ken_burns(index),
+            direction => direction,
+        });
+        render_slide(slide, &clip_path, options, motion)?;
+        clips.push(clip_path);
+    }
+    if let Some(outro) = &options.outro {
+        let clip_path = temp_dir.path().join("outro.mp4");
+        render_title_card(outro, &clip_path, options)?;
+        clips.push(clip_path);
+    }
+
+    let durations: Vec<f64> = parts.iter().map(|(_, duration)| *duration).collect();
+    let total = durations.iter().sum::<f64>() - overlap * (clips.len() - 1) as f64;
+
+    // Inputs have to come before any output option, so the video options are collected first
+    let mut command = Command::new("ffmpeg");
+    let mut video_args: Vec<String> = Vec::new();
+    let audio_input = match options.transition {
+        Some(transition) if clips.len() > 1 => {
+            // Chain xfade filters, each starting one transition before the end of the video so far
+            let mut filter_complex = String::new();
+            let mut previous = "0:v".to_string();
+            let mut offset = 0.0;
+            for (index, clip) in clips.iter().enumerate() {
+                command.arg("-i").arg(clip);
+                if index == 0 {
+                    continue;
+                }
+                offset += durations[index - 1] - overlap;
+                let label = if index + 1 == clips.len() { "v".to_string() } else { format!("x{}", index) };
+                filter_complex.push_str(&format!(
+                    "[{}][{}:v]xfade=transition={}:duration={}:offset={:.3}[{}];",
+                    previous, index, transition.filter_name(), overlap, offset, label
+                ));
+                previous = label;
+            }
+            filter_complex.pop();
+
+            video_args.extend(["-filter_complex".to_string(), filter_complex]);
+            video_args.extend(["-map", "[v]", "-c:v", "libx264", "-preset", "fast", "-crf", "18", "-pix_fmt", "yuv420p"].map(String::from));
+            clips.len()
+        }
+        _ => {
+            // Write the list of clips to a file for the concat demuxer
+            let list_path = temp_dir.path().join("clips.txt");
+            let clip_list: String = clips.iter().map(|clip| format!("{}\n", concat_list_entry(clip))).collect();
+            std::fs::write(&list_path, clip_list)?;
+
+            command.args(["-f", "concat", "-safe", "0", "-i"]).arg(&list_path);
+            video_args.extend(["-map", "0:v", "-c:v", "copy"].map(String::from));
+            1
+        }
+    };
+
+    // Loop the music if it is too short, cut it at the end of the slideshow and fade it in and out
+    if let Some(audio) = &audio {
+        command.args(["-stream_loop", "-1", "-i"]).arg(audio);
+    }
+    command.args(&video_args);
+    if audio.is_some() {
+        let fade = options.audio_fade.clamp(0.0, total / 2.0);
+        command.arg("-map")
+               .arg(format!("{}:a:0", audio_input))
+               .arg("-af")
+               .arg(format!("afade=t=in:st=0:d={:.3},afade=t=out:st={:.3}:d={:.3}", fade, total - fade, fade))
+               .args(["-c:a", "aac", "-t"])
+               .arg(format!("{:.3}", total));
+    }
+
+    let output = command.arg("-y").arg(&output_path).output()?;
+
+    if !output.status.success() {
+        return Err(format!("FFmpeg command failed: {}", String::from_utf8_lossy(&output.stderr)).into());
+    }
+
+    println!("Slideshow created successfully with {} images and {} title card(s) ({:.2} seconds)!", slides.len(), title_count, total);
+    for (index, slide) in slides.iter().enumerate() {
+        match &slide.caption {
+            Some(caption) => println!("  {}. {} ({:.2}s) - {}", index + 1, slide.path.display(), slide.duration, caption),
+            None => println!("  {}. {} ({:.2}s)", index + 1, slide.path.display(), slide.duration),
+        }
+    }
+    Ok(())
+}
+
+fn slide_duration(duration: f64) -> f64 {
+    // Rendered slides are a whole number of frames long
+    slide_frames(duration) as f64 / SLIDESHOW_FPS as f64
+}
+
+fn slide_frames(duration: f64) -> u32 {
+    (duration * SLIDESHOW_FPS as f64).round().max(1.0) as u32
+}
+
+fn render_slide(slide: &Slide, clip_path: &Path, options: &SlideshowOptions, motion: Option<KenBurns>) -> io::Result<()> {
+    let (width, height) = (even(options.size.0), even(options.size.1));
+    let frames = slide_frames(slide.duration);
+
+    // Fit the image into the canvas; Ken Burns works on a double-size canvas so the motion stays smooth
+    let scale = if motion.is_some() { 2 } else { 1 };
+    let (fit_width, fit_height) = (width * scale, height * scale);
+    // Turn the image upright first; automatic rotation is disabled below so it is applied only once
+    let mut filter = exif_orientation_filter(&slide.path).map(|filter| format!("{},", filter)).unwrap_or_default();
+    filter.push_str(&match options.fit {
+        SlideFit::Contain => format!(
+            "scale={w}:{h}:force_original_aspect_ratio=decrease:force_divisible_by=2,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:color=black,setsar=1",
+            w = fit_width, h = fit_height
+        ),
+        SlideFit::Cover => format!(
+            "scale={w}:{h}:force_original_aspect_ratio=increase,crop={w}:{h},setsar=1",
+            w = fit_width, h = fit_height
+        ),
+    });
+
+    if let Some(motion) = motion {
+        // Zoom up to 20%, or pan across an image zoomed in by 20%
+        let (zoom, x, y) = match motion {
+            KenBurns::ZoomIn => (format!("1+0.2*on/{}", frames), "iw/2-(iw/zoom/2)".to_string(), "ih/2-(ih/zoom/2)".to_string()),
+            KenBurns::ZoomOut => (format!("1.2-0.2*on/{}", frames), "iw/2-(iw/zoom/2)".to_string(), "ih/2-(ih/zoom/2)".to_string()),
+            KenBurns::PanLeft => ("1.2".to_string(), format!("(iw-iw/zoom)*(1-on/{})", frames), "ih/2-(ih/zoom/2)".to_string()),
+            KenBurns::PanRight => ("1.2".to_string(), format!("(iw-iw/zoom)*on/{}", frames), "ih/2-(ih/zoom/2)".to_string()),
+            KenBurns::PanUp => ("1.2".to_string(), "iw/2-(iw/zoom/2)".to_string(), format!("(ih-ih/zoom)*(1-on/{})", frames)),
+            KenBurns::PanDown | KenBurns::Random => ("1.2".to_string(), "iw/2-(iw/zoom/2)".to_string(), format!("(ih-ih/zoom)*on/{}", frames)),
+        };
+        filter.push_str(&format!(
+            ",zoompan=z='{}':x='{}':y='{}':d={}:s={}x{}:fps={}",
+            zoom, x, y, frames, width, height, SLIDESHOW_FPS
+        ));
+    }
+    // Draw the caption on the final canvas so it stays still while the image moves
+    if let Some(caption) = &slide.caption {
+        let caption_path = clip_path.with_extension("txt");
+        std::fs::write(&caption_path, caption)?;
+        let y = match options.caption_position {
+            CaptionPosition::Top => "h*0.05",
+            CaptionPosition::Center => "(h-text_h)/2",
+            CaptionPosition::Bottom => "h-text_h-h*0.05",
+        };
+        filter.push_str(&format!(",{}:x=(w-text_w)/2:y={}", drawtext_filter(&caption_path, options.font_size, options), y));
+        if options.caption_box {
+            filter.push_str(&format!(":box=1:boxcolor={}:boxborderw={}", options.box_color, options.font_size / 3));
+        }
+    }
+
+    filter.push_str(",format=yuv420p");
+
+    // A still image is looped for the slide duration; with Ken Burns zoompan expands the single frame instead
+    let mut command = Command::new("ffmpeg");
+    command.arg("-noautorotate");
+    if motion.is_none() {
+        command.arg("-loop").arg("1").arg("-framerate").arg(SLIDESHOW_FPS.to_string());
+    }
+    command.arg("-i")
+           .arg(&slide.path)
+           .arg("-vf")
+           .arg(&filter)
+           .arg("-frames:v")
+           .arg(frames.to_string())
+           .arg("-r")
+           .arg(SLIDESHOW_FPS.to_string())
+           .arg("-c:v")
+           .arg("libx264")
+           .arg("-preset")
+           .arg("fast")
+           .arg("-crf")
+           .arg("18")
+           .arg("-y")
+           .arg(clip_path);
+
+    let output = command.output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let error_message = String::from_utf8_lossy(&output.stderr);
+        Err(io::Error::other(format!("FFmpeg command failed for {}: {}", slide.path.display(), error_message)))
+    }
+}
+
+fn render_title_card(text: &str, clip_path: &Path, options: &SlideshowOptions) -> io::Result<()> {
+    let (width, height) = (even(options.size.0), even(options.size.1));
+    let frames = slide_frames(options.title_duration);
+
+    // A solid color source, turned into a vertical gradient when two colors are given
+    let colors: Vec<&str> = options.title_background.split(',').map(str::trim).collect();
+    let (source_color, mut filter) = match colors[..] {
+        [top, bottom] => {
+            let (r1, g1, b1) = hex_to_rgb(top)?;
+            let (r2, g2, b2) = hex_to_rgb(bottom)?;
+            let gradient = |from: u8, to: u8| format!("{}+({}-{})*Y/H", from, to, from);
+            (
+                "black".to_string(),
+                format!("format=rgb24,geq=r='{}':g='{}':b='{}'", gradient(r1, r2), gradient(g1, g2), gradient(b1, b2)),
+            )
+        }
+        _ => (options.title_background.clone(), "null".to_string()),
+    };
+
+    let text_path = clip_path.with_extension("txt");
+    std::fs::write(&text_path, text)?;
+    filter.push_str(&format!(
+        ",{}:x=(w-text_w)/2:y=(h-text_h)/2,format=yuv420p",
+        drawtext_filter(&text_path, options.font_size * 2, options)
+    ));
+
+    let output = Command::new("ffmpeg")
+        .args(["-f", "lavfi", "-i"])
+        .arg(format!("color=c={}:s={}x{}:r={}", source_color, width, height, SLIDESHOW_FPS))
+        .arg("-vf")
+        .arg(&filter)
+        .arg("-frames:v")
+        .arg(frames.to_string())
+        .args(["-c:v", "libx264", "-preset", "fast", "-crf", "18", "-y"])
+        .arg(clip_path)
+        .output()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let error_message = String::from_utf8_lossy(&output.stderr);
+        Err(io::Error::other(format!("FFmpeg command failed for title card: {}", error_message)))
+    }
+}
+
+fn drawtext_filter(text_path: &Path, font_size: u32, options: &SlideshowOptions) -> String {
+    // Reading the text from a file avoids escaping captions inside the filter graph
+    let mut filter = format!(
+        "drawtext=textfile={}:expansion=none:fontsize={}:fontcolor={}",
+        filter_quote(&text_path.display().to_string()), font_size, options.font_color
+    );
+    if let Some(font) = &options.font {
+        filter.push_str(&format!(":fontfile={}", filter_quote(font)));
+    }
+    filter
+}
+
+fn filter_quote(value: &str) -> String {
+    // Quote a filter option value, escaping embedded single quotes
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn random_ken_burns(index: usize) -> KenBurns {
+    use std::hash::{BuildHasher, Hasher};
+
+    // RandomState is seeded per process, which is random enough to vary the motion between runs
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_usize(index);
+    let directions = [KenBurns::ZoomIn, KenBurns::ZoomOut, KenBurns::PanLeft, KenBurns::PanRight, KenBurns::PanUp, KenBurns::PanDown];
+    directions[(hasher.finish() % directions.len() as u64) as usize]
+}
+
+fn collect_slides(input_path: &Path, sort: SlideOrder, duration: f64) -> io::Result<Vec<Slide>> {
+    let mut images = Vec::new();
+    for entry in std::fs::read_dir(input_path)? {
+        let entry = entry?;
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        // Trust known image extensions, otherwise ask FFprobe what the file is
+        if is_image_file(&path) || probe_is_image(&path) {
+            images.push(path);
+        } else {
+            eprintln!("Warning: skipping {}: not a supported image", path.display());
+        }
+    }
+
+    // read_dir order is arbitrary, so always sort
+    match sort {
+        SlideOrder::Name => images.sort(),
+        SlideOrder::Natural => images.sort_by(|a, b| natural_cmp(&file_name_of(a), &file_name_of(b))),
+        SlideOrder::Mtime => images.sort_by_key(|path| (modified_seconds(path), file_name_of(path))),
+        SlideOrder::ExifDate => {
+            let mut keyed: Vec<(i64, PathBuf)> = images.into_iter()
+                .map(|path| (exif_date_seconds(&path).unwrap_or_else(|| modified_seconds(&path)), path))
+                .collect();
+            keyed.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| natural_cmp(&file_name_of(&a.1), &file_name_of(&b.1))));
+            images = keyed.into_iter().map(|(_, path)| path).collect();
+        }
+    }
+
+    Ok(images.into_iter().map(|path| Slide { path, duration, caption: None }).collect())
+}
+
+fn read_manifest(manifest_path: &Path, input_path: &Path, default_duration: f64) -> Result<Vec<Slide>, Box<dyn std::error::Error>> {
+    // One image per line: "image | duration | caption", where duration and caption are optional.
+    // Blank lines and lines starting with '#' are skipped; relative paths are resolved against the input folder.
+    let contents = std::fs::read_to_string(manifest_path)
+        .map_err(|e| format!("Could not read manifest {}: {}", manifest_path.display(), e))?;
+
+    let mut slides = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.splitn(3, '|').map(str::trim);
+        let file = fields.next().unwrap_or("");
+        let path = make_absolute_path(file, input_path);
+        if !path.is_file() {
+            return Err(format!("{}:{}: image not found: {}", manifest_path.display(), number + 1, path.display()).into());
+        }
+
+        let duration = match fields.next().filter(|value| !value.is_empty()) {
+            Some(value) => value.parse::<f64>()
+                .ok()
+                .filter(|duration| *duration > 0.0)
+                .ok_or_else(|| format!("{}:{}: invalid duration '{}'", manifest_path.display(), number + 1, value))?,
+            None => default_duration,
+        };
+        let caption = fields.next().filter(|value| !value.is_empty()).map(str::to_string);
+
+        slides.push(Slide { path, duration, caption });
+    }
+
+    Ok(slides)
+}
+
+fn file_name_of(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    // Compare runs of digits by value so "img2" sorts before "img10"
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return std::cmp::Ordering::Less,
+            (Some(_), None) => return std::cmp::Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let mut x_digits = String::new();
+                while let Some(c) = a_chars.peek().copied().filter(char::is_ascii_digit) {
+                    x_digits.push(c);
+                    a_chars.next();
+                }
+                let mut y_digits = String::new();
+                while let Some(c) = b_chars.peek().copied().filter(char::is_ascii_digit) {
+                    y_digits.push(c);
+                    b_chars.next();
+                }
+                let x_trimmed = x_digits.trim_start_matches('0');
+                let y_trimmed = y_digits.trim_start_matches('0');
+                let ordering = x_trimmed.len().cmp(&y_trimmed.len()).then_with(|| x_trimmed.cmp(y_trimmed));
+                if ordering != std::cmp::Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != std::cmp::Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+fn modified_seconds(path: &Path) -> i64 {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn exif_date_seconds(path: &Path) -> Option<i64> {
+    // FFmpeg exposes the EXIF tags of an image as frame metadata
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0", "-show_entries", "frame_tags=DateTimeOriginal", "-of", "default=noprint_wrappers=1:nokey=1"])
+        .arg(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    parse_exif_date(String::from_utf8_lossy(&output.stdout).trim())
+}
+
+fn parse_exif_date(date: &str) -> Option<i64> {
+    // EXIF dates are written as "YYYY:MM:DD HH:MM:SS"
+    let numbers: Vec<i64> = date
+        .split([':', ' '])
+        .filter_map(|part| part.parse::<i64>().ok())
+        .collect();
+    let [year, month, day, hour, minute, second] = numbers[..] else { return None };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Days since the Unix epoch for a proleptic Gregorian date
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+fn make_absolute_path(path: &str, current_dir: &Path) -> PathBuf {
+    let path_buf = PathBuf::from(path);
+    if path_buf.is_absolute() {
+        path_buf
+    } else {
+        current_dir.join(path_buf)
     }
 }
 
 fn is_image_file(path: &Path) -> bool {
     let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-    matches!(extension.to_lowercase().as_str(), "jpg" | "jpeg" | "png" | "gif" | "bmp")
-}
\ No newline at end of file
+    matches!(
+        extension.to_lowercase().as_str(),
+        "jpg" | "jpeg" | "jfif" | "png" | "gif" | "bmp" | "webp" | "tif" | "tiff" | "heic" | "heif" | "avif" | "jxl" | "tga" | "qoi"
+    )
+}
+
+fn probe_is_image(path: &Path) -> bool {
+    // FFmpeg reads single images through the image2 demuxer or one of the *_pipe demuxers
+    let output = match Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries", "format=format_name", "-of", "default=noprint_wrappers=1:nokey=1"])
+        .arg(path)
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return false,
+    };
+    let format_name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    format_name == "image2" || format_name.ends_with("_pipe")
+}
+
+fn exif_orientation_filter(path: &Path) -> Option<&'static str> {
+    // FFmpeg exposes the EXIF tags of an image as frame metadata
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0", "-show_entries", "frame_tags=Orientation", "-of", "default=noprint_wrappers=1:nokey=1"])
+        .arg(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // Undo each of the eight EXIF orientations
+    match String::from_utf8_lossy(&output.stdout).trim().parse::<u8>().ok()? {
+        2 => Some("hflip"),
+        3 => Some("hflip,vflip"),
+        4 => Some("vflip"),
+        5 => Some("transpose=0"),
+        6 => Some("transpose=1"),
+        7 => Some("transpose=3"),
+        8 => Some("transpose=2"),
+        _ => None,
+    }
+}
+
+fn concat_list_entry(path: &Path) -> String {
+    // Quote the path for the concat demuxer, escaping embedded single quotes
+    format!("file '{}'", path.display().to_string().replace('\'', "'\\''"))
+}
+
+fn even(value: u32) -> u32 {
+    // Round down to an even size, never below 2
+    max(2, value & !1)
+}
+
+fn container_extension(input: &str) -> String {
+    // Use the extension of the input, falling back to mp4 when there is none
+    Path::new(input)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .filter(|e| !e.is_empty())
+        .unwrap_or_else(|| "mp4".to_string())
+}
+
+fn read_segment_list(list_path: &Path, output_path: &Path) -> io::Result<Vec<PathBuf>> {
+    // The segment muxer writes the file name of each segment it produced, one per line
+    let contents = std::fs::read_to_string(list_path)?;
+    Ok(contents.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|name| output_path.join(name))
+        .collect())
+}
+
+fn report_durations(files: &[PathBuf]) -> io::Result<()> {
+    for file in files {
+        let duration = get_video_duration(file.to_str().unwrap())?;
+        println!("  {} ({})", file.display(), format_timestamp(duration));
+    }
+    Ok(())
+}
+
+fn format_timestamp(seconds: f64) -> String {
+    // Format seconds as HH:MM:SS.mmm
+    let total_millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    let hours = total_millis / 3_600_000;
+    let minutes = (total_millis / 60_000) % 60;
+    let secs = (total_millis / 1000) % 60;
+    let millis = total_millis % 1000;
+    format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, secs, millis)
+}
+
+fn sanitize_filename(name: &str) -> String {
+    // Replace characters that are invalid in file names on common platforms
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Leading and trailing dots and spaces are not allowed on Windows, also after truncating
+    let is_trimmed = |c: char| c == '.' || c.is_whitespace();
+    let truncated: String = sanitized.trim_matches(is_trimmed).chars().take(100).collect();
+    let sanitized = truncated.trim_matches(is_trimmed);
+
+    if sanitized.is_empty() {
+        "untitled".to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+fn run_ffmpeg(command: &mut Command) -> io::Result<()> {
+    // Print the command being run
+    println!("Running FFmpeg command: {:?}", command);
+
+    // Run the command, capturing stdout and stderr together so neither pipe can fill up
+    let output = command.stdout(Stdio::piped())
+                        .stderr(Stdio::piped())
+                        .output()?;
+
+    // Print stdout and stderr
+    println!("FFmpeg stdout:\n{}", String::from_utf8_lossy(&output.stdout));
+    println!("FFmpeg stderr:\n{}", String::from_utf8_lossy(&output.stderr));
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("FFmpeg command failed with status: {}", output.status)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyframe(time: f64, speed: f64) -> SpeedKeyframe {
+        SpeedKeyframe { time, speed }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn speed_keyframes_parse_with_optional_suffix() {
+        let parsed = parse_speed_keyframe("7:4x").unwrap();
+        assert_close(parsed.time, 7.0);
+        assert_close(parsed.speed, 4.0);
+        let parsed = parse_speed_keyframe(" 2.5 : 0.5X ").unwrap();
+        assert_close(parsed.time, 2.5);
+        assert_close(parsed.speed, 0.5);
+    }
+
+    #[test]
+    fn speed_keyframes_reject_invalid_values() {
+        for value in ["7", "7:0", "7:-2", "-1:2", "2:inf", "inf:2", "NaN:2", "2:NaN", "a:2"] {
+            assert!(parse_speed_keyframe(value).is_err(), "{} should be rejected", value);
+        }
+    }
+
+    #[test]
+    fn constant_speed_is_one_segment() {
+        let segments = speed_segments(&[keyframe(0.0, 2.0)], 10.0, 0.5);
+        assert_eq!(segments.len(), 1);
+        assert_close(segments[0].0, 0.0);
+        assert_close(segments[0].1, 10.0);
+        assert_close(segments[0].2, 2.0);
+    }
+
+    #[test]
+    fn ramps_are_stepped_between_keyframes() {
+        let keyframes = [keyframe(0.0, 1.0), keyframe(5.0, 1.0), keyframe(7.0, 4.0), keyframe(12.0, 1.0)];
+        let segments = speed_segments(&keyframes, 15.0, 0.5);
+
+        // Held speed before the ramp and after the last keyframe
+        assert_close(segments[0].0, 0.0);
+        assert_close(segments[0].1, 5.0);
+        assert_close(segments[0].2, 1.0);
+        let last = segments[segments.len() - 1];
+        assert_close(last.0, 12.0);
+        assert_close(last.1, 15.0);
+        assert_close(last.2, 1.0);
+
+        // Segments cover the timeline without gaps, and steps never straddle a keyframe
+        for pair in segments.windows(2) {
+            assert_close(pair[0].1, pair[1].0);
+        }
+        for time in [7.0, 12.0] {
+            assert!(segments.iter().any(|segment| (segment.1 - time).abs() < 1e-6), "no boundary at {}", time);
+        }
+
+        // The ramp up to 7s uses the speed at the middle of each 0.5s step
+        let ramp_up: Vec<f64> = segments.iter().filter(|s| s.0 >= 5.0 && s.1 <= 7.0).map(|s| s.2).collect();
+        assert_eq!(ramp_up.len(), 4);
+        for (speed, expected) in ramp_up.iter().zip([1.375, 2.125, 2.875, 3.625]) {
+            assert_close(*speed, expected);
+        }
+        assert!(segments.iter().all(|s| s.2 >= 1.0 && s.2 <= 4.0));
+    }
+
+    #[test]
+    fn keyframes_past_the_end_ramp_until_the_end() {
+        let segments = speed_segments(&[keyframe(0.0, 1.0), keyframe(20.0, 3.0)], 10.0, 1.0);
+        assert_eq!(segments.len(), 10);
+        assert_close(segments[9].1, 10.0);
+        assert!(segments.windows(2).all(|pair| pair[1].2 > pair[0].2));
+        assert!(segments[9].2 < 2.0);
+    }
+
+    #[test]
+    fn keyframes_at_the_same_time_jump() {
+        let segments = speed_segments(&[keyframe(0.0, 1.0), keyframe(5.0, 1.0), keyframe(5.0, 3.0)], 10.0, 0.5);
+        assert_eq!(segments.len(), 2);
+        assert_close(segments[0].1, 5.0);
+        assert_close(segments[0].2, 1.0);
+        assert_close(segments[1].2, 3.0);
+    }
+
+    #[test]
+    fn even_rounds_down_to_at_least_two() {
+        assert_eq!(even(0), 2);
+        assert_eq!(even(1), 2);
+        assert_eq!(even(3), 2);
+        assert_eq!(even(7), 6);
+        assert_eq!(even(1080), 1080);
+    }
+
+    #[test]
+    fn aspect_crops_take_the_largest_area() {
+        assert_eq!(crop_rect(1920, 1080, CropTarget::Aspect(1, 1), Anchor::Center, None, None), (1080, 1080, 420, 0));
+        assert_eq!(crop_rect(1920, 1080, CropTarget::Aspect(16, 9), Anchor::Center, None, None), (1920, 1080, 0, 0));
+        // 1080 * 9 / 16 = 607.5, rounded down to an even width and centered on an even offset
+        assert_eq!(crop_rect(1920, 1080, CropTarget::Aspect(9, 16), Anchor::Center, None, None), (606, 1080, 656, 0));
+    }
+
+    #[test]
+    fn odd_sources_give_even_crops() {
+        assert_eq!(crop_rect(1921, 1081, CropTarget::Aspect(1, 1), Anchor::Center, None, None), (1080, 1080, 420, 0));
+        assert_eq!(crop_rect(1920, 1080, CropTarget::Size(101, 51), Anchor::TopLeft, None, None), (100, 50, 0, 0));
+        assert_eq!(crop_rect(1920, 1080, CropTarget::Size(100, 100), Anchor::TopLeft, Some(11), Some(13)), (100, 100, 10, 12));
+    }
+
+    #[test]
+    fn anchors_place_the_crop() {
+        let size = CropTarget::Size(640, 360);
+        assert_eq!(crop_rect(1920, 1080, size, Anchor::TopLeft, None, None), (640, 360, 0, 0));
+        assert_eq!(crop_rect(1920, 1080, size, Anchor::Top, None, None), (640, 360, 640, 0));
+        assert_eq!(crop_rect(1920, 1080, size, Anchor::Left, None, None), (640, 360, 0, 360));
+        assert_eq!(crop_rect(1920, 1080, size, Anchor::BottomRight, None, None), (640, 360, 1280, 720));
+        assert_eq!(crop_rect(1080, 1920, CropTarget::Aspect(4, 5), Anchor::Bottom, None, None), (1080, 1350, 0, 570));
+        // A crop as large as the video can only sit at the origin, whatever the anchor
+        assert_eq!(crop_rect(1920, 1080, CropTarget::Aspect(16, 9), Anchor::BottomRight, None, None), (1920, 1080, 0, 0));
+    }
+
+    #[test]
+    fn offsets_are_clamped_inside_the_video() {
+        assert_eq!(crop_rect(1920, 1080, CropTarget::Size(640, 360), Anchor::Center, Some(-50), Some(5000)), (640, 360, 0, 720));
+        // Sizes larger than the video shrink to fit, keeping their shape
+        assert_eq!(crop_rect(1920, 1080, CropTarget::Size(3840, 2160), Anchor::Center, None, None), (1920, 1080, 0, 0));
+    }
+
+    #[test]
+    fn natural_order_compares_numbers_by_value() {
+        use std::cmp::Ordering::*;
+        assert_eq!(natural_cmp("img2.jpg", "img10.jpg"), Less);
+        assert_eq!(natural_cmp("img10.jpg", "img9.jpg"), Greater);
+        assert_eq!(natural_cmp("IMG_2.jpg", "img_10.jpg"), Less);
+        assert_eq!(natural_cmp("photo", "photo1"), Less);
+        assert_eq!(natural_cmp("a1b2", "a1b10"), Less);
+        assert_eq!(natural_cmp("img12345678901234567890.jpg", "img9.jpg"), Greater);
+    }
+
+    #[test]
+    fn natural_order_breaks_ties_between_equal_numbers() {
+        use std::cmp::Ordering::*;
+        // Leading zeros do not change the value, so the names themselves decide, consistently both ways
+        assert_eq!(natural_cmp("img007.jpg", "img7.jpg"), natural_cmp("img7.jpg", "img007.jpg").reverse());
+        assert_ne!(natural_cmp("img007.jpg", "img7.jpg"), Equal);
+        assert_eq!(natural_cmp("img007.jpg", "img8.jpg"), Less);
+        assert_eq!(natural_cmp("img0.jpg", "img00.jpg"), natural_cmp("img00.jpg", "img0.jpg").reverse());
+        assert_eq!(natural_cmp("same.jpg", "same.jpg"), Equal);
+
+        let mut names = vec!["img10.jpg", "img007.jpg", "img1.jpg", "img7.jpg", "img02.jpg"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["img1.jpg", "img02.jpg", "img007.jpg", "img7.jpg", "img10.jpg"]);
+    }
+
+    #[test]
+    fn exif_dates_convert_to_unix_time() {
+        assert_eq!(parse_exif_date("1970:01:01 00:00:00"), Some(0));
+        assert_eq!(parse_exif_date("1969:12:31 23:59:59"), Some(-1));
+        assert_eq!(parse_exif_date("2000:03:01 00:00:00"), Some(951868800));
+        assert_eq!(parse_exif_date("2024:02:29 12:34:56"), Some(1709210096));
+        // Century years are leap years only every 400 years
+        assert_eq!(parse_exif_date("1900:03:01 00:00:00"), Some(-2203891200));
+        assert_eq!(parse_exif_date("2100:02:28 00:00:00"), Some(4107456000));
+    }
+
+    #[test]
+    fn invalid_exif_dates_are_ignored() {
+        assert_eq!(parse_exif_date(""), None);
+        assert_eq!(parse_exif_date("2024:02:29"), None);
+        assert_eq!(parse_exif_date("2024:13:01 00:00:00"), None);
+        assert_eq!(parse_exif_date("2024:00:01 00:00:00"), None);
+        assert_eq!(parse_exif_date("2024:01:32 00:00:00"), None);
+        assert_eq!(parse_exif_date("    :  :     :  :  "), None);
+    }
+
+    #[test]
+    fn timecodes_accept_seconds_and_colon_forms() {
+        assert_close(parse_timecode("0").unwrap(), 0.0);
+        assert_close(parse_timecode("90.5").unwrap(), 90.5);
+        assert_close(parse_timecode("1:30.5").unwrap(), 90.5);
+        assert_close(parse_timecode("00:01:30.500").unwrap(), 90.5);
+        assert_close(parse_timecode(" 1:00:00 ").unwrap(), 3600.0);
+        // The leading field is not limited to 60
+        assert_close(parse_timecode("90:00").unwrap(), 5400.0);
+    }
+
+    #[test]
+    fn timecodes_reject_invalid_values() {
+        for value in ["", "abc", "-1", "inf", "NaN", "1:-30", "1:75", "1:60", "1::2", "1:2:3:4", "1:inf"] {
+            assert!(parse_timecode(value).is_err(), "{} should be rejected", value);
+        }
+    }
+
+    fn presets_from(contents: &str) -> Result<Vec<Preset>, String> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("presets.ini");
+        std::fs::write(&path, contents).unwrap();
+        read_presets_file(&path, &builtin_presets()).map_err(|e| e.to_string())
+    }
+
+    fn preset_args(preset: &Preset) -> String {
+        preset.args().join(" ")
+    }
+
+    #[test]
+    fn unset_codecs_are_left_to_ffmpeg() {
+        let presets = presets_from("[video]\nvideo_codec = libx264\n\n[quality]\ncrf = 20\n").unwrap();
+        assert_eq!(presets[0].video_codec, StreamCodec::Encoder("libx264".to_string()));
+        assert_eq!(presets[0].audio_codec, StreamCodec::Default);
+        assert_eq!(preset_args(&presets[0]), "-c:v libx264");
+        assert_eq!(preset_args(&presets[1]), "-crf 20");
+    }
+
+    #[test]
+    fn none_drops_a_stream() {
+        let presets = presets_from("[podcast]\nvideo_codec = none\naudio_codec = libopus\naudio_bitrate = 64k\ncontainer = .opus\nargs = -ac 1\n").unwrap();
+        assert_eq!(presets[0].video_codec, StreamCodec::Drop);
+        assert_eq!(presets[0].container, "opus");
+        assert_eq!(preset_args(&presets[0]), "-vn -c:a libopus -b:a 64k -ac 1");
+    }
+
+    #[test]
+    fn presets_inherit_from_a_base() {
+        let contents = "# comment\n; another comment\n[preview]\nbase = web-h264\ncrf = 28\nprofile =\n\n[mute]\nbase = preview\naudio_codec = none\n";
+        let presets = presets_from(contents).unwrap();
+
+        let preview = &presets[0];
+        assert_eq!(preview.name, "preview");
+        assert_eq!(preview.description, "Based on web-h264");
+        assert_eq!(preview.crf, Some(28));
+        assert_eq!(preview.profile, None);
+        assert_eq!(preview.container, "mp4");
+        assert_eq!(preset_args(preview), "-c:v libx264 -crf 28 -preset medium -pix_fmt yuv420p -c:a aac -b:a 128k -movflags +faststart");
+
+        // A base can be an earlier preset of the same file
+        let mute = &presets[1];
+        assert_eq!(mute.name, "mute");
+        assert_eq!(mute.crf, Some(28));
+        assert_eq!(preset_args(mute), "-c:v libx264 -crf 28 -preset medium -pix_fmt yuv420p -an -movflags +faststart");
+    }
+
+    #[test]
+    fn invalid_preset_files_report_the_line() {
+        let cases = [
+            ("crf = 20\n", "presets.ini:1: 'crf' appears before any [preset] section"),
+            ("[a]\nfoo = 1\n", "presets.ini:2: unknown key 'foo'"),
+            ("[a]\nbase = missing\n", "presets.ini:2: unknown base preset 'missing'"),
+            ("[a]\nbase = a\n", "presets.ini:2: unknown base preset 'a'"),
+            ("[a]\n\ncrf = high\n", "presets.ini:3: invalid crf 'high'"),
+            ("[a]\ncrf 20\n", "presets.ini:2: expected 'key = value'"),
+            ("[ ]\n", "presets.ini:1: empty preset name"),
+        ];
+        for (contents, expected) in cases {
+            let error = presets_from(contents).unwrap_err();
+            assert!(error.contains(expected), "expected '{}' in '{}'", expected, error);
+        }
+    }
+
+    #[test]
+    fn user_presets_replace_built_in_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("presets.ini");
+        std::fs::write(&path, "[web-h264]\nbase = web-h264\ncrf = 30\n").unwrap();
+        let presets = load_presets(Some(path.to_str().unwrap())).unwrap();
+        let matching: Vec<&Preset> = presets.iter().filter(|preset| preset.name == "web-h264").collect();
+        assert_eq!(matching.len(), 1);
+        assert_eq!(matching[0].crf, Some(30));
+        assert_eq!(presets.len(), builtin_presets().len());
+    }
+
+    #[test]
+    fn file_sizes_parse_decimal_and_binary_units() {
+        assert_eq!(parse_file_size("1024"), Ok(1024));
+        assert_eq!(parse_file_size("500b"), Ok(500));
+        assert_eq!(parse_file_size("8MB"), Ok(8_000_000));
+        assert_eq!(parse_file_size("50M"), Ok(50_000_000));
+        assert_eq!(parse_file_size("8 mb"), Ok(8_000_000));
+        assert_eq!(parse_file_size("1.5GB"), Ok(1_500_000_000));
+        assert_eq!(parse_file_size("750k"), Ok(750_000));
+        assert_eq!(parse_file_size("700MiB"), Ok(734_003_200));
+        assert_eq!(parse_file_size("2KiB"), Ok(2048));
+        assert_eq!(parse_file_size("1GiB"), Ok(1_073_741_824));
+    }
+
+    #[test]
+    fn file_sizes_reject_invalid_values() {
+        for value in ["", "MB", "8XB", "0", "0MB", "0.4", "-5MB", "1.2.3MB", "inf"] {
+            assert!(parse_file_size(value).is_err(), "{} should be rejected", value);
+        }
+    }
+
+    #[test]
+    fn bitrates_parse_to_kbps() {
+        assert_eq!(parse_kbps("128k"), Some(128.0));
+        assert_eq!(parse_kbps("96K"), Some(96.0));
+        assert_eq!(parse_kbps("1.5M"), Some(1500.0));
+        assert_eq!(parse_kbps("128000"), Some(128.0));
+        assert_eq!(parse_kbps(" 64k "), Some(64.0));
+    }
+
+    #[test]
+    fn bitrates_reject_invalid_values() {
+        for value in ["", "k", "abc", "0", "0k", "-5k", "infk", "NaN"] {
+            assert_eq!(parse_kbps(value), None, "{} should be rejected", value);
+        }
+    }
+
+    #[test]
+    fn chapters_parse_from_probe_blocks() {
+        let output = "[CHAPTER]\nstart_time=0.000000\nend_time=62.500000\nTAG:title=Intro: the setup\n[/CHAPTER]\n\
+                      [CHAPTER]\nstart_time=62.500000\nend_time=120.000000\nTAG:title=  \n[/CHAPTER]\n\
+                      [CHAPTER]\nstart_time=120.000000\nend_time=120.000000\nTAG:title=Empty\n[/CHAPTER]\n\
+                      [CHAPTER]\nstart_time=120.000000\nend_time=180.250000\n[/CHAPTER]\n";
+        let chapters = parse_chapters(output);
+        assert_eq!(chapters.len(), 3);
+        assert_close(chapters[0].start, 0.0);
+        assert_close(chapters[0].end, 62.5);
+        assert_eq!(chapters[0].title.as_deref(), Some("Intro: the setup"));
+        assert_close(chapters[1].start, 62.5);
+        assert_eq!(chapters[1].title, None);
+        assert_close(chapters[2].end, 180.25);
+        assert_eq!(chapters[2].title, None);
+        assert!(parse_chapters("").is_empty());
+    }
+
+    #[test]
+    fn file_names_replace_reserved_characters() {
+        assert_eq!(sanitize_filename("Intro: the setup"), "Intro_ the setup");
+        assert_eq!(sanitize_filename("a/b\\c*d?e\"f<g>h|i"), "a_b_c_d_e_f_g_h_i");
+        assert_eq!(sanitize_filename("tab\there"), "tab_here");
+        assert_eq!(sanitize_filename("  ..Chapter 1.. "), "Chapter 1");
+    }
+
+    #[test]
+    fn empty_file_names_become_untitled() {
+        for name in ["", "   ", "...", " . . "] {
+            assert_eq!(sanitize_filename(name), "untitled", "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn file_names_are_capped_at_100_characters() {
+        assert_eq!(sanitize_filename(&"a".repeat(150)), "a".repeat(100));
+        assert_eq!(sanitize_filename(&"é".repeat(150)).chars().count(), 100);
+        // Truncation does not leave a trailing space or dot behind
+        let name = format!("{} {}", "a".repeat(99), "b".repeat(10));
+        assert_eq!(sanitize_filename(&name), "a".repeat(99));
+    }
+
+    fn scene(start: f64, end: f64, score: f64) -> Scene {
+        Scene { start, end, score }
+    }
+
+    fn assert_scenes(actual: &[Scene], expected: &[(f64, f64, f64)]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (scene, &(start, end, score)) in actual.iter().zip(expected) {
+            assert_close(scene.start, start);
+            assert_close(scene.end, end);
+            assert_close(scene.score, score);
+        }
+    }
+
+    #[test]
+    fn scene_cuts_parse_from_metadata_log() {
+        let log = "Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'in.mp4':\n\
+                   [Parsed_metadata_1 @ 0x55d0c0a0] frame:0    pts:52224   pts_time:4.08\n\
+                   [Parsed_metadata_1 @ 0x55d0c0a0] lavfi.scene_score=0.512345\n\
+                   [Parsed_metadata_1 @ 0x55d0c0a0] frame:1    pts:130560  pts_time:10.2\n\
+                   [Parsed_metadata_1 @ 0x55d0c0a0] lavfi.scene_score=0.900000\n\
+                   [Parsed_metadata_1 @ 0x55d0c0a0] frame:2    pts:153600  pts_time:12\n";
+        let cuts = parse_scene_cuts(log);
+        assert_eq!(cuts.len(), 3);
+        assert_close(cuts[0].0, 4.08);
+        assert_close(cuts[0].1, 0.512345);
+        assert_close(cuts[1].0, 10.2);
+        assert_close(cuts[1].1, 0.9);
+        // A cut whose score line is missing keeps a score of zero
+        assert_close(cuts[2].0, 12.0);
+        assert_close(cuts[2].1, 0.0);
+        assert!(parse_scene_cuts("lavfi.scene_score=0.5\n").is_empty());
+    }
+
+    #[test]
+    fn scenes_cover_the_whole_duration() {
+        let scenes = scenes_from_cuts(&[(4.0, 0.5), (10.0, 0.9)], 20.0, 0.0);
+        assert_scenes(&scenes, &[(0.0, 4.0, 0.0), (4.0, 10.0, 0.5), (10.0, 20.0, 0.9)]);
+        assert_scenes(&scenes_from_cuts(&[], 20.0, 1.0), &[(0.0, 20.0, 0.0)]);
+    }
+
+    #[test]
+    fn short_scenes_are_merged_into_the_previous_one() {
+        let cuts = [(0.0, 0.7), (1.0, 0.4), (1.5, 0.6), (5.0, 0.8), (19.0, 0.9)];
+        let scenes = scenes_from_cuts(&cuts, 20.0, 2.0);
+        // The cut at 0 would leave an empty first scene, 1.5 is too close to 1, and 19 too close to the end
+        assert_scenes(&scenes, &[(0.0, 5.0, 0.0), (5.0, 20.0, 0.8)]);
+        let scenes = scenes_from_cuts(&cuts, 20.0, 1.0);
+        assert_scenes(&scenes, &[(0.0, 1.0, 0.0), (1.0, 5.0, 0.4), (5.0, 19.0, 0.8), (19.0, 20.0, 0.9)]);
+    }
+
+    #[test]
+    fn scene_lists_format_as_json_and_csv() {
+        let scenes = [scene(0.0, 4.0, 0.0), scene(4.0, 10.5, 0.51234)];
+        assert_eq!(
+            format_scene_list(&scenes, ListFormat::Json),
+            "[\n  {\"index\": 1, \"start\": 0.000, \"end\": 4.000, \"duration\": 4.000, \"score\": 0.000},\n  \
+             {\"index\": 2, \"start\": 4.000, \"end\": 10.500, \"duration\": 6.500, \"score\": 0.512}\n]\n"
+        );
+        assert_eq!(
+            format_scene_list(&scenes, ListFormat::Csv),
+            "index,start,end,duration,score\n1,0.000,4.000,4.000,0.000\n2,4.000,10.500,6.500,0.512\n"
+        );
+        assert_eq!(
+            format_scene_list(&scenes[1..], ListFormat::Text),
+            "Scene 1: 00:00:04.000 - 00:00:10.500 (6.500s, score 0.512)\n"
+        );
+    }
+
+    #[test]
+    fn atempo_chains_split_large_changes() {
+        assert_eq!(atempo_chain(1.0).unwrap(), "atempo=1.000000");
+        assert_eq!(atempo_chain(1.5).unwrap(), "atempo=1.500000");
+        assert_eq!(atempo_chain(4.0).unwrap(), "atempo=2.0,atempo=2.0,atempo=1.000000");
+        assert_eq!(atempo_chain(5.0).unwrap(), "atempo=2.0,atempo=2.0,atempo=1.250000");
+        assert_eq!(atempo_chain(0.1).unwrap(), "atempo=0.5,atempo=0.5,atempo=0.5,atempo=0.800000");
+        assert_eq!(atempo_chain(0.25).unwrap(), "atempo=0.5,atempo=0.5,atempo=1.000000");
+    }
+
+    #[test]
+    fn atempo_chains_multiply_back_to_the_tempo() {
+        for tempo in [0.01, 0.3, 0.75, 2.5, 7.0, 100.0] {
+            let product: f64 = atempo_chain(tempo).unwrap()
+                .split(',')
+                .map(|filter| filter.trim_start_matches("atempo=").parse::<f64>().unwrap())
+                .product();
+            assert!((product - tempo).abs() < 1e-5 * tempo, "{} gave {}", tempo, product);
+        }
+    }
+
+    #[test]
+    fn atempo_chains_reject_invalid_tempos() {
+        for tempo in [0.0, -1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(atempo_chain(tempo).is_err(), "{} should be rejected", tempo);
+        }
+    }
+
+    #[test]
+    fn frame_sequences_start_at_the_lowest_number() {
+        let names = ["frame_1003.png", "frame_1001.png", "frame_1002.png", "notes.txt", "frame_1000.jpg"];
+        assert_eq!(lowest_frame_number("frame_%d.png", names), Some(1001));
+        assert_eq!(lowest_frame_number("frame_%04d.png", names), Some(1001));
+        let names = ["shot.0007.exr", "shot.0012.exr", "shot.0003.exr.bak"];
+        assert_eq!(lowest_frame_number("shot.%04d.exr", names), Some(7));
+    }
+
+    #[test]
+    fn frame_sequences_match_the_padding_exactly() {
+        // %05d never expands to 1001 and %d never expands to 01001
+        assert_eq!(lowest_frame_number("frame_%05d.png", ["frame_1001.png"]), None);
+        assert_eq!(lowest_frame_number("frame_%d.png", ["frame_01001.png", "frame_2000.png"]), Some(2000));
+        // Numbers wider than the padding are still part of the sequence
+        assert_eq!(lowest_frame_number("frame_%03d.png", ["frame_1000.png", "frame_999.png"]), Some(999));
+        assert_eq!(lowest_frame_number("frame_%d.png", ["frame_.png", "frame_+5.png", "frame_-5.png"]), None);
+    }
+
+    #[test]
+    fn frame_sequences_need_a_number_placeholder() {
+        assert_eq!(lowest_frame_number("frame.png", ["frame.png"]), None);
+        assert_eq!(lowest_frame_number("frame_%xd.png", ["frame_1.png"]), None);
+        assert_eq!(lowest_frame_number("frame_%d.png", std::iter::empty()), None);
+    }
+
+    #[test]
+    fn progress_reports_give_the_final_frame_count() {
+        let progress = "frame=120\nfps=60.00\nout_time=00:00:04.000000\nprogress=continue\n\
+                        frame=250\nfps=61.20\nout_time=00:00:08.333333\nprogress=end\n";
+        assert_eq!(progress_frames(progress), Some(250));
+        assert_eq!(progress_frames("frame=0\nprogress=end\n"), Some(0));
+        assert_eq!(progress_frames(""), None);
+    }
+
+    #[test]
+    fn sprite_tracks_address_tiles_row_by_row() {
+        let track = sprite_track(20.0, 10.0, 2, 2, 160, 90, "thumbs", "jpg");
+        assert_eq!(
+            track,
+            "WEBVTT\n\
+             \n00:00:00.000 --> 00:00:10.000\nthumbs_001.jpg#xywh=0,0,160,90\n\
+             \n00:00:10.000 --> 00:00:20.000\nthumbs_001.jpg#xywh=160,0,160,90\n"
+        );
+    }
+
+    #[test]
+    fn sprite_tracks_roll_over_to_the_next_sheet() {
+        // Seven thumbnails on 2x2 sheets: four on the first, three on the second
+        let track = sprite_track(33.0, 5.0, 2, 2, 160, 90, "thumbs", "jpg");
+        let cues: Vec<&str> = track.lines().filter(|line| line.contains("#xywh=")).collect();
+        assert_eq!(
+            cues,
+            [
+                "thumbs_001.jpg#xywh=0,0,160,90",
+                "thumbs_001.jpg#xywh=160,0,160,90",
+                "thumbs_001.jpg#xywh=0,90,160,90",
+                "thumbs_001.jpg#xywh=160,90,160,90",
+                "thumbs_002.jpg#xywh=0,0,160,90",
+                "thumbs_002.jpg#xywh=160,0,160,90",
+                "thumbs_002.jpg#xywh=0,90,160,90",
+            ]
+        );
+        // The last cue stops at the end of the video
+        assert!(track.ends_with("\n00:00:30.000 --> 00:00:33.000\nthumbs_002.jpg#xywh=0,90,160,90\n"));
+    }
+
+    #[test]
+    fn sprite_tracks_have_at_least_one_cue() {
+        let track = sprite_track(2.5, 10.0, 5, 5, 120, 68, "sprite", "webp");
+        assert_eq!(track, "WEBVTT\n\n00:00:00.000 --> 00:00:02.500\nsprite_001.webp#xywh=0,0,120,68\n");
+    }
+}
@@ -35,6 +35,36 @@ enum Commands {
         /// Output video file
         #[arg(value_name = "OUTPUT")]
         output: String,
+
+        /// Length in seconds of the chunks reversed at a time (limits memory use on long videos)
+        #[arg(short, long, default_value = "5")]
+        chunk: f64,
+    },
+    /// Play a video forward then backward as a seamless loop
+    Boomerang {
+        /// Input video file
+        #[arg(value_name = "INPUT")]
+        input: String,
+
+        /// Output video file
+        #[arg(value_name = "OUTPUT")]
+        output: String,
+
+        /// Number of forward and backward cycles
+        #[arg(short, long, default_value = "1")]
+        loops: u32,
+
+        /// Speed factor applied to both directions
+        #[arg(short, long, default_value = "1")]
+        speed: f64,
+
+        /// How to treat the audio track
+        #[arg(short, long, value_enum, default_value = "mute")]
+        audio: ffmpeg::BoomerangAudio,
+
+        /// Length in seconds of the chunks reversed at a time
+        #[arg(short, long, default_value = "5")]
+        chunk: f64,
     },
     /// Split a video into multiple parts
     Split {
@@ -47,8 +77,34 @@ enum Commands {
         output_dir: String,
 
         /// Number of parts to split the video into
-        #[arg(value_name = "PARTS")]
-        parts: u32,
+        #[arg(value_name = "PARTS", required_unless_present = "by_chapters")]
+        parts: Option<u32>,
+
+        /// Split on the chapter markers of the input, naming each part after its chapter title
+        #[arg(long, conflicts_with = "parts")]
+        by_chapters: bool,
+    },
+    /// Detect scene changes and list or split the scenes
+    Scenes {
+        /// Input video file
+        #[arg(value_name = "INPUT")]
+        input: String,
+
+        /// Scene-change score (0.0 - 1.0) above which a frame starts a new scene
+        #[arg(short, long, default_value = "0.4")]
+        threshold: f64,
+
+        /// Minimum scene length in seconds; shorter scenes are merged into their neighbours
+        #[arg(short, long, default_value = "0")]
+        min_length: f64,
+
+        /// Format of the printed scene list
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ffmpeg::ListFormat,
+
+        /// Also split the video into one clip per scene in this directory
+        #[arg(short, long, value_name = "OUTPUT_DIR")]
+        split: Option<String>,
     },
     Stretch {
         /// Input video file
@@ -62,6 +118,36 @@ enum Commands {
         /// Target duration in seconds
         #[arg(value_name = "DURATION")]
         duration: f64,
+
+        /// How to treat the audio track
+        #[arg(short, long, value_enum, default_value = "preserve")]
+        pitch: ffmpeg::PitchMode,
+
+        /// Synthesize intermediate frames for smooth slow motion
+        #[arg(short, long, value_enum)]
+        interpolate: Option<ffmpeg::Interpolation>,
+
+        /// Frame rate of the interpolated output (default: frame rate of the input)
+        #[arg(long, requires = "interpolate")]
+        fps: Option<f64>,
+    },
+    /// Change playback speed over time using speed keyframes
+    Speed {
+        /// Input video file
+        #[arg(value_name = "INPUT")]
+        input: String,
+
+        /// Output video file
+        #[arg(value_name = "OUTPUT")]
+        output: String,
+
+        /// Speed keyframes as TIME:SPEED (e.g. 5:1 7:4 12:1), interpolated linearly in between
+        #[arg(value_name = "KEYFRAMES", num_args = 1.., required = true, value_parser = ffmpeg::parse_speed_keyframe)]
+        keyframes: Vec<ffmpeg::SpeedKeyframe>,
+
+        /// Length in seconds of the constant-speed steps used to approximate ramps
+        #[arg(long, default_value = "0.25")]
+        step: f64,
     },
     /// Cover a watermark with a shape
     CoverWatermark {
@@ -143,7 +229,93 @@ enum Commands {
         #[arg(value_name = "OUTPUT", short, long)]
         output: String,
     },
-    /// Crop video to 1:1 square
+    /// Crop video to an aspect ratio or an exact size
+    Crop {
+        /// Input video file
+        #[arg(value_name = "INPUT")]
+        input: String,
+
+        /// Output video file
+        #[arg(value_name = "OUTPUT")]
+        output: String,
+
+        /// Aspect ratio of the crop area as W:H (e.g. 9:16, 4:5, 16:9)
+        #[arg(short, long, value_parser = ffmpeg::parse_aspect, required_unless_present_any = ["size", "auto"], conflicts_with = "size")]
+        aspect: Option<(u32, u32)>,
+
+        /// Size of the crop area as WxH (will be scaled down to fit the video)
+        #[arg(short, long, value_parser = ffmpeg::parse_size)]
+        size: Option<(u32, u32)>,
+
+        /// Where to place the crop area when no offsets are given
+        #[arg(long, value_enum, default_value = "center")]
+        anchor: ffmpeg::Anchor,
+
+        /// X-coordinate of the top-left corner of the crop area (overrides the anchor)
+        #[arg(short = 'x', long)]
+        x_offset: Option<i32>,
+
+        /// Y-coordinate of the top-left corner of the crop area (overrides the anchor)
+        #[arg(short = 'y', long)]
+        y_offset: Option<i32>,
+
+        /// Detect and remove black bars; --aspect/--size then apply inside the detected area
+        #[arg(long)]
+        auto: bool,
+    },
+    /// Reframe video to a new aspect ratio, following the motion in each shot
+    Reframe {
+        /// Input video file
+        #[arg(value_name = "INPUT")]
+        input: String,
+
+        /// Output video file
+        #[arg(value_name = "OUTPUT")]
+        output: String,
+
+        /// Aspect ratio of the output as W:H (e.g. 9:16, 1:1)
+        #[arg(short, long, value_parser = ffmpeg::parse_aspect, default_value = "9:16")]
+        aspect: (u32, u32),
+
+        /// Scene-change score (0.0 - 1.0) used to split the video into shots
+        #[arg(short, long, default_value = "0.4")]
+        threshold: f64,
+
+        /// Window in seconds over which motion is averaged; larger values pan more calmly
+        #[arg(short, long, default_value = "2")]
+        smoothing: f64,
+    },
+    /// Fit video into a canvas, filling the empty space with a background
+    Fit {
+        /// Input video file
+        #[arg(value_name = "INPUT")]
+        input: String,
+
+        /// Output video file
+        #[arg(value_name = "OUTPUT")]
+        output: String,
+
+        /// Size of the canvas as WxH
+        #[arg(short, long, value_parser = ffmpeg::parse_size, default_value = "1080x1920")]
+        canvas: (u32, u32),
+
+        /// What fills the space around the video
+        #[arg(short, long, value_enum, default_value = "blur")]
+        background: ffmpeg::FitBackground,
+
+        /// Background color (name or hexadecimal, e.g., '#FF0000') for the color background
+        #[arg(long, default_value = "#000000")]
+        color: String,
+
+        /// Background image for the image background
+        #[arg(long, required_if_eq("background", "image"))]
+        image: Option<String>,
+
+        /// Blur radius for the blur background
+        #[arg(long, default_value = "20")]
+        blur: u32,
+    },
+    /// Crop video to 1:1 square (same as `crop --aspect 1:1`)
     SquareCrop {
         /// Input video file
         #[arg(value_name = "INPUT")]
@@ -165,6 +337,220 @@ enum Commands {
         #[arg(short = 'y', long)]
         y_offset: Option<i32>,
     },
+    /// Create a video from a numbered image sequence or a glob of images
+    FramesToVideo {
+        /// Image pattern, either numbered (frames/frame_%05d.png) or a quoted glob ('frames/*.png')
+        #[arg(value_name = "PATTERN")]
+        pattern: String,
+
+        /// Output video file
+        #[arg(value_name = "OUTPUT")]
+        output: String,
+
+        /// Frames per second
+        #[arg(short, long, default_value = "24")]
+        fps: f64,
+
+        /// Number of the first image of a numbered sequence (default: lowest number in the folder)
+        #[arg(long)]
+        start_number: Option<u32>,
+
+        /// Constant rate factor (lower is better quality)
+        #[arg(long, default_value = "18")]
+        crf: u32,
+    },
+    /// Export the frames of a video as images
+    VideoToFrames {
+        /// Input video file
+        #[arg(value_name = "INPUT")]
+        input: String,
+
+        /// Output directory for the images
+        #[arg(value_name = "OUTPUT_DIR")]
+        output_dir: String,
+
+        /// Keep only every Nth frame
+        #[arg(short, long, conflicts_with = "fps", value_parser = clap::value_parser!(u32).range(1..))]
+        every: Option<u32>,
+
+        /// Sample frames at a fixed rate, e.g. 1 for one image per second
+        #[arg(short, long)]
+        fps: Option<f64>,
+
+        /// Image format
+        #[arg(long, value_enum, default_value = "png")]
+        format: ffmpeg::FrameFormat,
+
+        /// Image quality from 1 to 100 for jpg and webp
+        #[arg(short, long, default_value = "90")]
+        quality: u32,
+
+        /// File name prefix of the images
+        #[arg(long, default_value = "frame")]
+        prefix: String,
+    },
+    /// Extract still images at timecodes, evenly spaced, or the most representative frame
+    Thumbnail {
+        /// Input video file
+        #[arg(value_name = "INPUT")]
+        input: String,
+
+        /// Output image (.jpg, .png or .webp); several stills are numbered poster_01.jpg, poster_02.jpg, ...
+        #[arg(value_name = "OUTPUT")]
+        output: String,
+
+        /// Timecodes of the stills, in seconds or HH:MM:SS.mmm (default: most representative frame)
+        #[arg(long, num_args = 1.., value_parser = ffmpeg::parse_timecode)]
+        at: Vec<f64>,
+
+        /// Number of evenly spaced stills
+        #[arg(short, long, conflicts_with = "at")]
+        count: Option<u32>,
+
+        /// Width of the stills (keeps the aspect ratio when the height is not given)
+        #[arg(long)]
+        width: Option<u32>,
+
+        /// Height of the stills (keeps the aspect ratio when the width is not given)
+        #[arg(long)]
+        height: Option<u32>,
+
+        /// Image quality from 1 to 100 for jpg and webp
+        #[arg(short, long, default_value = "90")]
+        quality: u32,
+    },
+    /// Create a contact sheet: a grid of timestamped frames under a header describing the video
+    ContactSheet {
+        /// Input video file
+        #[arg(value_name = "INPUT")]
+        input: String,
+
+        /// Output image (.jpg, .png or .webp)
+        #[arg(value_name = "OUTPUT")]
+        output: String,
+
+        /// Number of columns in the grid
+        #[arg(short, long, default_value = "4")]
+        columns: u32,
+
+        /// Number of rows in the grid
+        #[arg(short, long, default_value = "4")]
+        rows: u32,
+
+        /// Width of each frame in pixels
+        #[arg(short, long, default_value = "320")]
+        width: u32,
+
+        /// Font file for the labels and header
+        #[arg(long)]
+        font: Option<String>,
+    },
+    /// Create seek-preview sprite sheets and a WebVTT thumbnails track
+    Sprites {
+        /// Input video file
+        #[arg(value_name = "INPUT")]
+        input: String,
+
+        /// Output directory for the sprite sheets and the track
+        #[arg(value_name = "OUTPUT_DIR")]
+        output_dir: String,
+
+        /// Seconds between thumbnails
+        #[arg(short, long, default_value = "5")]
+        interval: f64,
+
+        /// Width of each thumbnail in pixels
+        #[arg(short, long, default_value = "160")]
+        width: u32,
+
+        /// Number of columns per sprite sheet
+        #[arg(short, long, default_value = "10")]
+        columns: u32,
+
+        /// Number of rows per sprite sheet
+        #[arg(short, long, default_value = "10")]
+        rows: u32,
+
+        /// Image format of the sprite sheets
+        #[arg(long, value_enum, default_value = "jpg")]
+        format: ffmpeg::FrameFormat,
+
+        /// File name prefix of the sprite sheets and the track
+        #[arg(long, default_value = "sprite")]
+        prefix: String,
+    },
+    /// Export a high-quality GIF, animated WebP or APNG
+    Gif {
+        /// Input video file
+        #[arg(value_name = "INPUT")]
+        input: String,
+
+        /// Output animation (.gif, .webp or .apng)
+        #[arg(value_name = "OUTPUT")]
+        output: String,
+
+        /// Frames per second
+        #[arg(short, long, default_value = "15")]
+        fps: f64,
+
+        /// Width in pixels, the height follows the aspect ratio (default: width of the input)
+        #[arg(short, long)]
+        width: Option<u32>,
+
+        /// GIF dithering mode
+        #[arg(short, long, value_enum, default_value = "sierra2-4a")]
+        dither: ffmpeg::Dither,
+
+        /// Maximum number of GIF palette colors (2 - 256)
+        #[arg(long, default_value = "256")]
+        colors: u32,
+
+        /// How many times the animation plays, 0 for forever
+        #[arg(short = 'l', long = "loop", default_value = "0")]
+        plays: u32,
+
+        /// Start of the range to export, in seconds or HH:MM:SS.mmm
+        #[arg(long, value_parser = ffmpeg::parse_timecode)]
+        start: Option<f64>,
+
+        /// End of the range to export, in seconds or HH:MM:SS.mmm
+        #[arg(long, value_parser = ffmpeg::parse_timecode)]
+        end: Option<f64>,
+
+        /// WebP quality from 1 to 100
+        #[arg(short, long, default_value = "80")]
+        quality: u32,
+    },
+    /// Convert a video with a named preset
+    Transcode {
+        /// Input video file
+        #[arg(value_name = "INPUT", required_unless_present = "list_presets")]
+        input: Option<String>,
+
+        /// Output file (the extension of the preset is added when there is none)
+        #[arg(value_name = "OUTPUT", required_unless_present = "list_presets")]
+        output: Option<String>,
+
+        /// Preset: web-h264, web-vp9, av1, hevc-archive, prores-proxy, dnxhr, mp3, opus or a user preset
+        #[arg(short, long, default_value = "web-h264")]
+        preset: String,
+
+        /// Presets file (default: ~/.config/ffmpeg-cli/presets.ini when it exists)
+        #[arg(long)]
+        config: Option<String>,
+
+        /// List the available presets
+        #[arg(long)]
+        list_presets: bool,
+
+        /// Encode in two passes to land under this size, e.g. 8MB, 50M or 700MiB
+        #[arg(long, value_parser = ffmpeg::parse_file_size)]
+        target_size: Option<u64>,
+
+        /// Audio bitrate in kbps (default: the bitrate of the preset)
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        audio_bitrate: Option<u32>,
+    },
     /// Create a slideshow from a folder of images
     Slideshow {
         /// Input folder containing images (relative or absolute path)
@@ -177,7 +563,91 @@ enum Commands {
 
         /// Duration of each image in seconds
         #[arg(value_name = "DURATION", default_value = "3")]
-        duration: u32,
+        duration: f64,
+
+        /// Order of the images in the folder
+        #[arg(long, value_enum, default_value = "natural")]
+        sort: ffmpeg::SlideOrder,
+
+        /// Manifest listing images as "image | duration | caption", one per line
+        #[arg(short, long)]
+        manifest: Option<String>,
+
+        /// Size of the output video as WxH
+        #[arg(short, long, value_parser = ffmpeg::parse_size, default_value = "1920x1080")]
+        size: (u32, u32),
+
+        /// How images are fitted into the output size
+        #[arg(short, long, value_enum, default_value = "contain")]
+        fit: ffmpeg::SlideFit,
+
+        /// Add a Ken Burns pan/zoom to every image (random direction per image unless given)
+        #[arg(short, long, value_enum, num_args = 0..=1, default_missing_value = "random")]
+        ken_burns: Option<ffmpeg::KenBurns>,
+
+        /// Transition between images
+        #[arg(short, long, value_enum)]
+        transition: Option<ffmpeg::Transition>,
+
+        /// Length of each transition in seconds
+        #[arg(long, default_value = "1")]
+        transition_duration: f64,
+
+        /// Background music, looped or cut to the length of the slideshow
+        #[arg(short, long)]
+        audio: Option<String>,
+
+        /// Length of the music fade-in and fade-out in seconds
+        #[arg(long, default_value = "2")]
+        audio_fade: f64,
+
+        /// Adjust the image durations so the slideshow is as long as the music
+        #[arg(long, requires = "audio")]
+        fit_to_audio: bool,
+
+        /// Caption images with their file names when the manifest gives no caption
+        #[arg(long)]
+        caption_from_filename: bool,
+
+        /// Font file for captions and title cards
+        #[arg(long)]
+        font: Option<String>,
+
+        /// Caption font size in pixels (title cards use twice this size)
+        #[arg(long, default_value = "48")]
+        font_size: u32,
+
+        /// Text color of captions and title cards
+        #[arg(long, default_value = "white")]
+        font_color: String,
+
+        /// Draw a box behind captions
+        #[arg(long)]
+        caption_box: bool,
+
+        /// Color of the caption box
+        #[arg(long, default_value = "black@0.5")]
+        box_color: String,
+
+        /// Where captions are placed on the image
+        #[arg(long, value_enum, default_value = "bottom")]
+        caption_position: ffmpeg::CaptionPosition,
+
+        /// Title card shown before the first image
+        #[arg(long)]
+        intro: Option<String>,
+
+        /// Title card shown after the last image
+        #[arg(long)]
+        outro: Option<String>,
+
+        /// Duration of each title card in seconds
+        #[arg(long, default_value = "3")]
+        title_duration: f64,
+
+        /// Title card background: a color, or two hex colors separated by a comma for a gradient
+        #[arg(long, default_value = "black")]
+        title_background: String,
     },
 }
 
@@ -202,24 +672,47 @@ fn main() {
                 std::process::exit(1);
             }
         }
-        Commands::Reverse { input, output } => {
-            if let Err(e) = ffmpeg::reverse_video(input, output) {
+        Commands::Reverse { input, output, chunk } => {
+            if let Err(e) = ffmpeg::reverse_video(input, output, *chunk) {
                 eprintln!("Error reversing video: {}", e);
                 std::process::exit(1);
             }
         }
-        Commands::Split { input, output_dir, parts } => {
-            if let Err(e) = ffmpeg::split_video(input, output_dir, *parts) {
+        Commands::Boomerang { input, output, loops, speed, audio, chunk } => {
+            if let Err(e) = ffmpeg::boomerang(input, output, *loops, *speed, *audio, *chunk) {
+                eprintln!("Error creating boomerang: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Split { input, output_dir, parts, by_chapters } => {
+            let result = if *by_chapters {
+                ffmpeg::split_by_chapters(input, output_dir)
+            } else {
+                ffmpeg::split_video(input, output_dir, parts.unwrap_or(1))
+            };
+            if let Err(e) = result {
                 eprintln!("Error splitting video: {}", e);
                 std::process::exit(1);
             }
         }
-        Commands::Stretch { input, output, duration } => {
-            if let Err(e) = ffmpeg::stretch_video(input, output, *duration) {
+        Commands::Scenes { input, threshold, min_length, format, split } => {
+            if let Err(e) = ffmpeg::list_scenes(input, *threshold, *min_length, *format, split.as_deref()) {
+                eprintln!("Error detecting scenes: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Stretch { input, output, duration, pitch, interpolate, fps } => {
+            if let Err(e) = ffmpeg::stretch_video(input, output, *duration, *pitch, *interpolate, *fps) {
                 eprintln!("Error stretching video: {}", e);
                 std::process::exit(1);
             }
         }
+        Commands::Speed { input, output, keyframes, step } => {
+            if let Err(e) = ffmpeg::speed_ramp(input, output, keyframes, *step) {
+                eprintln!("Error changing video speed: {}", e);
+                std::process::exit(1);
+            }
+        }
         Commands::CoverWatermark { input, output, width, height, x, y, shape, color } => {
             if let Err(e) = ffmpeg::cover_watermark(input, output, *width, *height, *x, *y, *shape, color) {
                 eprintln!("Error covering watermark: {}", e);
@@ -244,14 +737,124 @@ fn main() {
                 std::process::exit(1);
             }
         }
+        Commands::Crop { input, output, aspect, size, anchor, x_offset, y_offset, auto } => {
+            let target = match (aspect, size) {
+                (_, Some((width, height))) => Some(ffmpeg::CropTarget::Size(*width, *height)),
+                (Some((width, height)), None) => Some(ffmpeg::CropTarget::Aspect(*width, *height)),
+                (None, None) => None,
+            };
+            if let Err(e) = ffmpeg::crop_video(input, output, target, *anchor, *x_offset, *y_offset, *auto) {
+                eprintln!("Error cropping video: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Reframe { input, output, aspect, threshold, smoothing } => {
+            if let Err(e) = ffmpeg::reframe_video(input, output, *aspect, *threshold, *smoothing) {
+                eprintln!("Error reframing video: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Fit { input, output, canvas, background, color, image, blur } => {
+            if let Err(e) = ffmpeg::fit_video(input, output, *canvas, *background, color, image.as_deref(), *blur) {
+                eprintln!("Error fitting video: {}", e);
+                std::process::exit(1);
+            }
+        }
         Commands::SquareCrop { input, output, size, x_offset, y_offset } => {
             if let Err(e) = ffmpeg::square_crop(input, output, *size, *x_offset, *y_offset) {
                 eprintln!("Error cropping video to square: {}", e);
                 std::process::exit(1);
             }
         }
-        Commands::Slideshow { input_folder, output, duration } => {
-            if let Err(e) = ffmpeg::create_slideshow(input_folder, output, *duration) {
+        Commands::FramesToVideo { pattern, output, fps, start_number, crf } => {
+            if let Err(e) = ffmpeg::frames_to_video(pattern, output, *fps, *start_number, *crf) {
+                eprintln!("Error creating video from frames: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::VideoToFrames { input, output_dir, every, fps, format, quality, prefix } => {
+            if let Err(e) = ffmpeg::video_to_frames(input, output_dir, *every, *fps, *format, *quality, prefix) {
+                eprintln!("Error exporting frames: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Thumbnail { input, output, at, count, width, height, quality } => {
+            if let Err(e) = ffmpeg::extract_thumbnails(input, output, at, *count, *width, *height, *quality) {
+                eprintln!("Error extracting thumbnails: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::ContactSheet { input, output, columns, rows, width, font } => {
+            if let Err(e) = ffmpeg::contact_sheet(input, output, *columns, *rows, *width, font.as_deref()) {
+                eprintln!("Error creating contact sheet: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Sprites { input, output_dir, interval, width, columns, rows, format, prefix } => {
+            if let Err(e) = ffmpeg::sprite_sheets(input, output_dir, *interval, *width, *columns, *rows, *format, prefix) {
+                eprintln!("Error creating sprite sheets: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Gif { input, output, fps, width, dither, colors, plays, start, end, quality } => {
+            let options = ffmpeg::AnimationOptions {
+                fps: *fps,
+                width: *width,
+                dither: *dither,
+                colors: *colors,
+                plays: *plays,
+                start: *start,
+                end: *end,
+                quality: *quality,
+            };
+            if let Err(e) = ffmpeg::create_animation(input, output, &options) {
+                eprintln!("Error creating animation: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Transcode { input, output, preset, config, list_presets, target_size, audio_bitrate } => {
+            let result = match (input, output) {
+                (Some(input), Some(output)) if !*list_presets => {
+                    ffmpeg::transcode(input, output, preset, config.as_deref(), *target_size, *audio_bitrate)
+                }
+                _ => ffmpeg::list_presets(config.as_deref()),
+            };
+            if let Err(e) = result {
+                eprintln!("Error transcoding video: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Slideshow {
+            input_folder, output, duration, sort, manifest, size, fit, ken_burns,
+            transition, transition_duration, audio, audio_fade, fit_to_audio,
+            caption_from_filename, font, font_size, font_color, caption_box, box_color, caption_position,
+            intro, outro, title_duration, title_background,
+        } => {
+            let options = ffmpeg::SlideshowOptions {
+                duration: *duration,
+                sort: *sort,
+                manifest: manifest.clone(),
+                size: *size,
+                fit: *fit,
+                ken_burns: *ken_burns,
+                transition: *transition,
+                transition_duration: *transition_duration,
+                audio: audio.clone(),
+                audio_fade: *audio_fade,
+                fit_to_audio: *fit_to_audio,
+                caption_from_filename: *caption_from_filename,
+                font: font.clone(),
+                font_size: *font_size,
+                font_color: font_color.clone(),
+                caption_box: *caption_box,
+                box_color: box_color.clone(),
+                caption_position: *caption_position,
+                intro: intro.clone(),
+                outro: outro.clone(),
+                title_duration: *title_duration,
+                title_background: title_background.clone(),
+            };
+            if let Err(e) = ffmpeg::create_slideshow(input_folder, output, &options) {
                 eprintln!("Error creating slideshow: {}", e);
                 std::process::exit(1);
             }